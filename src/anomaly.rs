@@ -0,0 +1,324 @@
+//! Anomaly detection on metric history for slow-rs.
+//!
+//! Static [`Thresholds`] can't tell that 40% CPU is abnormal for an idle
+//! NAS. This module compares the latest value of each metric against a
+//! rolling baseline of the preceding samples in `metrics_history`, using
+//! a robust z-score:
+//!
+//! ```text
+//! z = (value - median) / max(1.4826 × MAD, minimum spread)
+//! ```
+//!
+//! The median and MAD (median absolute deviation) ignore the occasional
+//! spike in the baseline, unlike a mean and standard deviation. The
+//! minimum spread per metric keeps a perfectly flat baseline (e.g. 0%
+//! I/O pressure) from turning every tiny blip into an anomaly.
+//!
+//! Only deviations in the harmful direction are flagged (more CPU, less
+//! available memory, slower benchmarks). Anomalies show up as
+//! recommendations, unless the metric's static threshold already fires,
+//! and as highlighted chart borders in the TUI.
+
+use std::collections::VecDeque;
+
+use crate::metrics::Metrics;
+use crate::thresholds::{Severity, Thresholds};
+
+/// Which deviation from the baseline is harmful.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    /// Higher than usual is bad (usage, pressure, latency, durations)
+    High,
+    /// Lower than usual is bad (available memory, throughput)
+    Low,
+}
+
+/// How a metric is checked for anomalies.
+#[derive(Debug)]
+pub struct AnomalyCheck {
+    /// Stable identifier (used to match charts)
+    pub key: &'static str,
+    /// Human-readable name
+    pub label: &'static str,
+    /// Unit appended to values
+    pub unit: &'static str,
+    /// Value extractor (benchmarks are None when they didn't run or ran
+    /// reduced, see [`Metrics::unreduced`])
+    pub value_fn: fn(&Metrics) -> Option<f64>,
+    /// Harmful direction
+    pub direction: Direction,
+    /// Smallest spread assumed, in the metric's unit
+    pub min_spread: f64,
+    /// Smallest spread assumed, as a fraction of the median
+    pub min_spread_ratio: f64,
+    /// Static threshold for the same metric (anomalies are not reported
+    /// as recommendations while it fires)
+    pub threshold: Option<fn(&Thresholds, f64) -> Severity>,
+    /// Where to look when the anomaly fires
+    pub hint: &'static str,
+}
+
+/// Metrics checked for anomalies.
+pub const CHECKS: &[AnomalyCheck] = &[
+    AnomalyCheck {
+        key: "cpu_usage",
+        label: "CPU Usage",
+        unit: "%",
+        value_fn: |m| Some(m.cpu_usage_percent as f64),
+        direction: Direction::High,
+        min_spread: 5.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(|t, v| t.cpu_usage_severity(v as f32)),
+        hint: "top, htop for new CPU-heavy processes",
+    },
+    AnomalyCheck {
+        key: "mem_available",
+        label: "Available Memory",
+        unit: " MB",
+        value_fn: |m| Some(m.mem_available_mb as f64),
+        direction: Direction::Low,
+        min_spread: 256.0,
+        min_spread_ratio: 0.05,
+        threshold: Some(|t, v| t.memory_available_severity(v as u64)),
+        hint: "ps aux --sort=-rss | head",
+    },
+    AnomalyCheck {
+        key: "iowait",
+        label: "I/O Wait",
+        unit: "%",
+        value_fn: Metrics::iowait_percent,
+        direction: Direction::High,
+        min_spread: 2.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::iowait_severity),
+        hint: "iotop -o, iostat -x 1",
+    },
+    AnomalyCheck {
+        key: "cpu_pressure",
+        label: "CPU Pressure",
+        unit: "%",
+        value_fn: |m| m.cpu_pressure_some_avg10,
+        direction: Direction::High,
+        min_spread: 2.0,
+        min_spread_ratio: 0.0,
+        threshold: None,
+        hint: "top for runnable tasks, CPU limits",
+    },
+    AnomalyCheck {
+        key: "mem_pressure",
+        label: "Memory Pressure",
+        unit: "%",
+        value_fn: |m| m.mem_pressure_some_avg10,
+        direction: Direction::High,
+        min_spread: 2.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::mem_pressure_severity),
+        hint: "free -h, vmstat 1",
+    },
+    AnomalyCheck {
+        key: "io_pressure",
+        label: "I/O Pressure",
+        unit: "%",
+        value_fn: |m| m.io_pressure_some_avg10,
+        direction: Direction::High,
+        min_spread: 2.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::io_pressure_severity),
+        hint: "iotop -o, iostat -x 1",
+    },
+    AnomalyCheck {
+        key: "major_faults",
+        label: "Major Page Faults",
+        unit: "",
+        value_fn: |m| Some(m.pgmajfault as f64),
+        direction: Direction::High,
+        min_spread: 50.0,
+        min_spread_ratio: 0.0,
+        threshold: None,
+        hint: "vmstat 1 for memory pressure or cold caches",
+    },
+    AnomalyCheck {
+        key: "cpu_temp",
+        label: "CPU Temperature",
+        unit: "°C",
+        value_fn: |m| m.cpu_temp_celsius,
+        direction: Direction::High,
+        min_spread: 3.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::cpu_temp_severity),
+        hint: "fans, airflow and CPU load",
+    },
+    AnomalyCheck {
+        key: "dimm_temp",
+        label: "RAM Temperature",
+        unit: "°C",
+        value_fn: |m| m.dimm_temp_max,
+        direction: Direction::High,
+        min_spread: 3.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::dimm_temp_severity),
+        hint: "case airflow and memory-heavy load",
+    },
+    AnomalyCheck {
+        key: "disk_temp",
+        label: "Disk Temperature",
+        unit: "°C",
+        value_fn: |m| m.disk_temp_max,
+        direction: Direction::High,
+        min_spread: 3.0,
+        min_spread_ratio: 0.0,
+        threshold: Some(Thresholds::disk_temp_severity),
+        hint: "drive cooling and I/O load",
+    },
+    AnomalyCheck {
+        key: "io_read",
+        label: "Sequential Read Throughput",
+        unit: " MB/s",
+        value_fn: |m| m.unreduced(m.io_read_mb_per_sec),
+        direction: Direction::Low,
+        min_spread: 0.0,
+        min_spread_ratio: 0.1,
+        threshold: None,
+        hint: "iostat -x 1, smartctl -a",
+    },
+    AnomalyCheck {
+        key: "iops_read_p99",
+        label: "Random Read p99 Latency",
+        unit: " µs",
+        value_fn: |m| m.unreduced(m.iops_read_p99_us),
+        direction: Direction::High,
+        min_spread: 0.0,
+        min_spread_ratio: 0.1,
+        threshold: Some(|t, v| t.io_latency_severity(v / 1000.0)),
+        hint: "iostat -x 1, smartctl -a",
+    },
+    AnomalyCheck {
+        key: "fsync_p99",
+        label: "fsync p99 Latency",
+        unit: " ms",
+        value_fn: |m| m.unreduced(m.fsync_p99_ms),
+        direction: Direction::High,
+        min_spread: 0.0,
+        min_spread_ratio: 0.1,
+        threshold: Some(Thresholds::io_latency_severity),
+        hint: "write-heavy processes and dirty page writeback",
+    },
+    AnomalyCheck {
+        key: "compute",
+        label: "Compute Benchmark Time",
+        unit: " ms",
+        value_fn: |m| m.compute_duration_ms,
+        direction: Direction::High,
+        min_spread: 0.0,
+        min_spread_ratio: 0.05,
+        threshold: None,
+        hint: "CPU frequency, throttling and competing load",
+    },
+    AnomalyCheck {
+        key: "wakeup_p99",
+        label: "Scheduler Wakeup p99",
+        unit: " µs",
+        value_fn: |m| m.wakeup_p99_us,
+        direction: Direction::High,
+        min_spread: 0.0,
+        min_spread_ratio: 0.2,
+        threshold: Some(Thresholds::wakeup_latency_severity),
+        hint: "CPU contention and power-saving states",
+    },
+];
+
+/// A metric that deviates from its baseline.
+#[derive(Clone, Debug)]
+pub struct Anomaly {
+    /// The check that fired
+    pub check: &'static AnomalyCheck,
+    /// Latest value
+    pub value: f64,
+    /// Baseline median
+    pub median: f64,
+    /// Robust z-score (positive = in the harmful direction)
+    pub z: f64,
+    /// Warning or Critical, from the z-score
+    pub severity: Severity,
+    /// Severity of the metric's static threshold for the same value
+    pub threshold_severity: Severity,
+}
+
+/// Find metrics whose latest value deviates from the preceding samples.
+///
+/// `history` may or may not already contain `metrics` (matched by
+/// timestamp). Benchmarks that didn't run in `metrics` are judged by their
+/// most recent run.
+pub fn detect_anomalies(
+    metrics: &Metrics,
+    history: &VecDeque<Metrics>,
+    thresholds: &Thresholds,
+) -> Vec<Anomaly> {
+    let earlier = || {
+        history
+            .iter()
+            .filter(move |m| m.timestamp < metrics.timestamp)
+    };
+
+    CHECKS
+        .iter()
+        .filter_map(|check| {
+            let mut values: Vec<f64> = earlier().filter_map(check.value_fn).collect();
+            let value = match (check.value_fn)(metrics) {
+                Some(value) => value,
+                None => values.pop()?,
+            };
+            if values.len() < thresholds.anomaly_min_samples {
+                return None;
+            }
+
+            let median = median_of(&mut values)?;
+            let mut deviations: Vec<f64> = values.iter().map(|v| (v - median).abs()).collect();
+            let mad = median_of(&mut deviations)?;
+            let spread = (1.4826 * mad)
+                .max(check.min_spread)
+                .max(check.min_spread_ratio * median.abs());
+            if spread <= 0.0 {
+                return None;
+            }
+
+            let z = match check.direction {
+                Direction::High => (value - median) / spread,
+                Direction::Low => (median - value) / spread,
+            };
+            let severity = if z >= thresholds.anomaly_z_critical {
+                Severity::Critical
+            } else if z >= thresholds.anomaly_z_warning {
+                Severity::Warning
+            } else {
+                return None;
+            };
+
+            Some(Anomaly {
+                check,
+                value,
+                median,
+                z,
+                severity,
+                threshold_severity: check
+                    .threshold
+                    .map(|f| f(thresholds, value))
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Median of a slice (sorts it in place); None if it is empty.
+fn median_of(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    })
+}
@@ -3,21 +3,97 @@
 //! This module contains the main [`App`] struct which coordinates
 //! metrics collection, logging, and the user interface.
 
-use std::collections::VecDeque;
-use std::fs::{File, OpenOptions};
-use std::path::Path;
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use chrono::Utc;
 use sysinfo::System;
 
 use crate::availability::MetricAvailability;
+use crate::baseline::{self, Baseline, BaselineRecorder, BaselineStore, MachineIdentity};
 use crate::benchmarks::{self, IoBenchmarkResult};
-use crate::collectors::{self, CpuStats, DiskStats, NetStats, VmStats};
-use crate::config::Config;
+use crate::collectors::{self, CpuStats, DiskStats, NetStats, ThrottleStats, VmStats};
+use crate::config::{Config, IoTarget};
+use crate::csvlog::{CsvLog, Rotation};
+use crate::database::Database;
+use crate::incidents::IncidentTracker;
+use crate::influx::InfluxSink;
 use crate::ipmi::IpmiSensors;
-use crate::metrics::Metrics;
+use crate::metrics::{Metrics, SCHEMA_VERSION};
+use crate::power::PowerInfo;
+use crate::prometheus::{PrometheusExporter, PrometheusRenderer, TextfileWriter};
+use crate::recommendations::generate_recommendations;
+use crate::sinks::{JsonlSink, MetricsSink};
 use crate::smart::SmartHealth;
-use crate::thresholds::Thresholds;
+use crate::thresholds::{Severity, Thresholds};
+
+/// Per-target benchmark results as (label, value) pairs.
+#[derive(Default)]
+struct IoTargetResults {
+    read: Vec<(String, f64)>,
+    write: Vec<(String, f64)>,
+    iops_read: Vec<(String, f64)>,
+    iops_read_p99: Vec<(String, f64)>,
+    iops_write: Vec<(String, f64)>,
+    fsync_p99: Vec<(String, f64)>,
+    meta_create: Vec<(String, f64)>,
+}
+
+/// Benchmark backoff decided by the `--adaptive` policy.
+#[derive(Default)]
+struct Backoff {
+    /// Shrink heavy benchmark workloads (Warning-level stress)
+    reduce: bool,
+    /// Skip heavy benchmarks entirely (Critical-level stress)
+    skip: bool,
+    /// Stress readings that caused the backoff (e.g., "io_pressure=31.2%")
+    reason: Option<String>,
+}
+
+/// Per-device deltas between two snapshots, keyed by device name.
+///
+/// Devices missing from the previous snapshot (e.g. just attached) are left out.
+fn device_deltas<T>(
+    last: Option<&[(String, T)]>,
+    current: Option<&[(String, T)]>,
+    delta: fn(&T, &T) -> T,
+) -> BTreeMap<String, T> {
+    let (Some(last), Some(current)) = (last, current) else {
+        return BTreeMap::new();
+    };
+    current
+        .iter()
+        .filter_map(|(name, cur)| {
+            let (_, prev) = last.iter().find(|(n, _)| n == name)?;
+            Some((name.clone(), delta(prev, cur)))
+        })
+        .collect()
+}
+
+/// Whether a benchmark group is due, given when it last ran and its interval.
+///
+/// Without an interval the group runs on every sample.
+fn schedule_due(last_run: Option<Instant>, interval_secs: Option<u64>) -> bool {
+    match (last_run, interval_secs) {
+        (Some(last), Some(secs)) => last.elapsed() >= Duration::from_secs(secs),
+        _ => true,
+    }
+}
+
+/// Format (label, value) pairs as a "label:value,..." list (None if empty).
+fn labeled_list(values: &[(String, f64)]) -> Option<String> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(
+        values
+            .iter()
+            .map(|(label, value)| format!("{}:{:.1}", label, value))
+            .collect::<Vec<_>>()
+            .join(","),
+    )
+}
 
 /// Main application state.
 ///
@@ -30,8 +106,8 @@ pub struct App {
     /// Historical metrics for plotting
     pub metrics_history: VecDeque<Metrics>,
 
-    /// CSV writer for logging
-    csv_writer: Option<csv::Writer<File>>,
+    /// CSV log
+    csv_log: CsvLog,
 
     /// System information collector
     sys: System,
@@ -42,12 +118,21 @@ pub struct App {
     /// Previous network stats for delta calculation
     last_net_stats: Option<NetStats>,
 
+    /// Previous per-device disk stats for per-device deltas
+    last_disk_devices: Option<Vec<(String, DiskStats)>>,
+
+    /// Previous per-interface network stats for per-interface deltas
+    last_net_interfaces: Option<Vec<(String, NetStats)>>,
+
     /// Previous CPU stats for delta calculation
     last_cpu_stats: Option<CpuStats>,
 
     /// Previous VM stats for delta calculation
     last_vm_stats: Option<VmStats>,
 
+    /// Previous thermal throttle counters for delta calculation
+    last_throttle_stats: Option<ThrottleStats>,
+
     /// Metric source availability
     pub availability: MetricAvailability,
 
@@ -65,6 +150,51 @@ pub struct App {
 
     /// Counter for IPMI collection interval
     ipmi_collection_counter: u32,
+
+    /// When the compute benchmark group last ran (for `--compute-interval`)
+    last_compute_bench: Option<Instant>,
+
+    /// When the I/O benchmark group last ran (for `--io-interval`)
+    last_io_bench: Option<Instant>,
+
+    /// Hostname and hardware fingerprint for baseline lookup
+    machine: MachineIdentity,
+
+    /// Path to the baseline state file
+    baseline_path: PathBuf,
+
+    /// Baseline for this machine (None if not recorded yet)
+    pub baseline: Option<Baseline>,
+
+    /// Accumulates samples when recording a new baseline
+    baseline_recorder: Option<BaselineRecorder>,
+
+    /// Prometheus renderer (if any Prometheus output is enabled)
+    prometheus_renderer: Option<PrometheusRenderer>,
+
+    /// Prometheus `/metrics` exporter (if `--prometheus-listen` was given)
+    prometheus: Option<PrometheusExporter>,
+
+    /// node_exporter textfile writer (if `--textfile-dir` was given)
+    textfile: Option<TextfileWriter>,
+
+    /// Structured output sinks (JSON Lines, InfluxDB)
+    sinks: Vec<Box<dyn MetricsSink>>,
+
+    /// SQLite storage (if `--sqlite-file` was given)
+    database: Option<Database>,
+
+    /// Incidents built from the recommendations of each sample
+    pub incidents: IncidentTracker,
+
+    /// Latest error of each optional output, until it succeeds again
+    output_errors: BTreeMap<&'static str, String>,
+
+    /// Output errors that are new in the latest sample
+    new_output_errors: Vec<String>,
+
+    /// Why the incident file couldn't be loaded (reported once, then None)
+    incident_error: Option<std::io::Error>,
 }
 
 impl App {
@@ -79,71 +209,251 @@ impl App {
     ///
     /// # Errors
     ///
-    /// Returns an error if the CSV file cannot be opened.
+    /// Returns an error if the CSV file cannot be opened, the baseline
+    /// state file exists but cannot be parsed, the Prometheus listen
+    /// address cannot be bound, the textfile directory doesn't exist, or
+    /// a sink's output file or the SQLite database cannot be opened.
     pub fn new(config: Config) -> std::io::Result<Self> {
-        // Initialize CSV writer (append mode, write header if new file)
-        let csv_exists = Path::new(&config.csv_file).exists();
-        let csv_file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(&config.csv_file)?;
-
-        let csv_writer = csv::WriterBuilder::new()
-            .has_headers(!csv_exists)
-            .from_writer(csv_file);
+        // Open the CSV log (append mode; an outdated file is rotated aside)
+        let (csv_log, rotated) = CsvLog::open(&config.csv_file, Rotation::from_config(&config))?;
+        if let Some(rotated) = rotated {
+            eprintln!(
+                "{} has an older column layout, moved it to {}",
+                config.csv_file,
+                rotated.display()
+            );
+        }
 
         let history_size = config.history_size;
 
         // Probe metric availability at startup
         let availability = MetricAvailability::probe();
 
+        let sys = System::new_all();
+
+        // Load the baseline for this machine (unless we're recording a new one)
+        let machine = MachineIdentity::detect(&sys);
+        let baseline_path = config
+            .baseline_file
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(baseline::default_path);
+        let baseline = if config.record_baseline {
+            None
+        } else {
+            BaselineStore::load(&baseline_path)?
+                .baselines
+                .remove(&machine.key())
+        };
+        let baseline_recorder = config.record_baseline.then(BaselineRecorder::default);
+
+        let prometheus = config
+            .prometheus_listen
+            .as_deref()
+            .map(PrometheusExporter::start)
+            .transpose()?;
+        let textfile = config
+            .textfile_dir
+            .as_deref()
+            .map(TextfileWriter::new)
+            .transpose()?;
+        let mut sinks: Vec<Box<dyn MetricsSink>> = Vec::new();
+        if let Some(ref path) = config.jsonl_file {
+            sinks.push(Box::new(JsonlSink::open(path)?));
+        }
+        if let Some(ref dest) = config.influx_output {
+            sinks.push(Box::new(InfluxSink::open(dest, &machine.hostname)?));
+        }
+        let database = config
+            .sqlite_file
+            .as_deref()
+            .map(|path| Database::open(path, config.sqlite_retention_days))
+            .transpose()?;
+        let prometheus_renderer =
+            (prometheus.is_some() || textfile.is_some()).then(PrometheusRenderer::default);
+
+        let thresholds = Thresholds::from_config(&config);
+
+        // An unreadable incident file is reported with the first sample
+        let (incidents, incident_error) = IncidentTracker::open(
+            config.incident_file.as_ref().map(PathBuf::from),
+            config.incident_recovery,
+        );
+
         Ok(Self {
             config,
             metrics_history: VecDeque::with_capacity(history_size),
-            csv_writer: Some(csv_writer),
-            sys: System::new_all(),
+            csv_log,
+            sys,
             last_disk_stats: None,
             last_net_stats: None,
+            last_disk_devices: None,
+            last_net_interfaces: None,
             last_cpu_stats: None,
             last_vm_stats: None,
+            last_throttle_stats: None,
             availability,
-            thresholds: Thresholds::default(),
+            thresholds,
             last_smart_health: None,
             smart_collection_counter: 0,
             last_ipmi_sensors: None,
             ipmi_collection_counter: 0,
+            last_compute_bench: None,
+            last_io_bench: None,
+            machine,
+            baseline_path,
+            baseline,
+            baseline_recorder,
+            prometheus_renderer,
+            prometheus,
+            textfile,
+            sinks,
+            database,
+            incidents,
+            output_errors: BTreeMap::new(),
+            new_output_errors: Vec::new(),
+            incident_error,
         })
     }
 
-    /// Ensure the I/O benchmark test file exists.
+    /// Warnings for the TUI: failing outputs, then unavailable metrics.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings: Vec<String> = self.output_errors.values().cloned().collect();
+        warnings.extend(self.availability.get_warnings());
+        warnings
+    }
+
+    /// Output errors that first appeared (or changed) in the latest sample.
+    pub fn new_output_errors(&self) -> &[String] {
+        &self.new_output_errors
+    }
+
+    /// Record the result of writing to an optional output.
     ///
-    /// If the file doesn't exist, creates it with the configured size.
-    /// This is skipped unless `--io-bench` was specified.
+    /// Outputs such as the textfile or a sink must not stop monitoring (the
+    /// sample is already in the CSV), so failures are kept for display
+    /// instead of being returned.
+    fn check_output(&mut self, output: &'static str, result: std::io::Result<()>) {
+        match result {
+            Ok(()) => {
+                self.output_errors.remove(output);
+            }
+            Err(e) => {
+                let message = format!("{} failed: {}", output, e);
+                if self.output_errors.get(output) != Some(&message) {
+                    self.new_output_errors.push(message.clone());
+                    self.output_errors.insert(output, message);
+                }
+            }
+        }
+    }
+
+    /// Ensure the I/O benchmark test files exist.
+    ///
+    /// If a file doesn't exist, creates it with the configured size.
+    /// The main test file is skipped unless `--io-bench` or `--iops-bench`
+    /// was specified; `--io-target` files are created when their benchmark
+    /// mix needs one.
     pub fn ensure_test_file(&self) -> std::io::Result<()> {
-        if !self.config.io_bench {
-            return Ok(());
+        if self.config.io_bench || self.config.iops_bench {
+            let path = Path::new(&self.config.test_file);
+            if !path.exists() {
+                benchmarks::create_test_file(&self.config.test_file, self.config.file_size_mb)?;
+            }
         }
 
-        let path = Path::new(&self.config.test_file);
-        if !path.exists() {
-            benchmarks::create_test_file(&self.config.test_file, self.config.file_size_mb)?;
+        for target in self
+            .config
+            .io_targets
+            .iter()
+            .filter(|t| t.needs_test_file())
+        {
+            let test_file = target.test_file();
+            if !Path::new(&test_file).exists() {
+                benchmarks::create_test_file(&test_file, self.target_size_mb(target))?;
+            }
         }
 
         Ok(())
     }
 
+    /// Test file size for an `--io-target`.
+    fn target_size_mb(&self, target: &IoTarget) -> usize {
+        target.size_mb.unwrap_or(self.config.file_size_mb)
+    }
+
+    /// Run the benchmark mix of every `--io-target`.
+    ///
+    /// `shrink` scales workload sizes down when the adaptive policy backs off.
+    /// A failing target (e.g. an unmounted path) only loses its own results.
+    fn benchmark_io_targets(&self, shrink: impl Fn(usize) -> usize) -> IoTargetResults {
+        let mut results = IoTargetResults::default();
+
+        for target in &self.config.io_targets {
+            let label = &target.path;
+            let test_file = target.test_file();
+
+            if target.io {
+                if let Ok(r) =
+                    benchmarks::benchmark_io(&test_file, shrink(self.target_size_mb(target)))
+                {
+                    results.read.push((label.clone(), r.read_mb_per_sec));
+                    results.write.push((label.clone(), r.write_mb_per_sec));
+                }
+            }
+            if target.iops {
+                if let Ok(r) = benchmarks::benchmark_random_io(
+                    &test_file,
+                    self.config.iops_queue_depth,
+                    Duration::from_millis(shrink(self.config.iops_duration_ms as usize) as u64),
+                ) {
+                    results.iops_read.push((label.clone(), r.read_iops));
+                    results
+                        .iops_read_p99
+                        .push((label.clone(), r.read_latency.p99_us));
+                    results.iops_write.push((label.clone(), r.write_iops));
+                }
+            }
+            if target.fsync {
+                if let Ok(r) =
+                    benchmarks::benchmark_fsync(&test_file, shrink(self.config.fsync_count))
+                {
+                    results
+                        .fsync_p99
+                        .push((label.clone(), r.fsync.p99_us / 1000.0));
+                }
+            }
+            if target.meta {
+                if let Ok(r) = benchmarks::benchmark_metadata(
+                    &target.dir(),
+                    shrink(self.config.metadata_files),
+                ) {
+                    results
+                        .meta_create
+                        .push((label.clone(), r.create_ops_per_sec));
+                }
+            }
+        }
+
+        results
+    }
+
     /// Collect all metrics and run benchmarks.
     ///
     /// This is the main collection function that:
-    /// 1. Runs active benchmarks (allocation, compute, I/O)
+    /// 1. Runs the benchmarks that are due (allocation, compute, I/O)
     /// 2. Reads system stats from sysinfo
     /// 3. Reads detailed stats from /proc
     /// 4. Calculates deltas from previous measurements
     /// 5. Logs the metrics to CSV
+    /// 6. Adds them to `metrics_history`, then feeds the recommendations
+    ///    (judged with the new sample in the history, as the TUI shows
+    ///    them) to the incident tracker and other outputs
     ///
     /// # Returns
     ///
-    /// The collected metrics snapshot.
+    /// The collected metrics snapshot (also the last entry of
+    /// `metrics_history`).
     pub fn collect_metrics(&mut self) -> std::io::Result<Metrics> {
         let now = Utc::now();
         let timestamp = now.timestamp();
@@ -153,11 +463,64 @@ impl App {
         self.sys.refresh_all();
 
         // === Run benchmarks ===
-        let alloc_duration = benchmarks::benchmark_allocation();
-        let compute_duration = benchmarks::benchmark_compute();
+        // Compute and I/O benchmarks each run on their own schedule
+        let compute_due = schedule_due(self.last_compute_bench, self.config.compute_interval);
+        let io_due = schedule_due(self.last_io_bench, self.config.io_interval);
+        if compute_due {
+            self.last_compute_bench = Some(Instant::now());
+        }
+        if io_due {
+            self.last_io_bench = Some(Instant::now());
+        }
 
-        let (io_read, io_write, sha_duration) = if self.config.io_bench {
-            match benchmarks::benchmark_io(&self.config.test_file, self.config.file_size_mb) {
+        let alloc_duration = compute_due.then(benchmarks::benchmark_allocation);
+        let compute_duration = compute_due.then(benchmarks::benchmark_compute);
+
+        // Back off heavy benchmarks while the system is already stressed
+        let backoff = self.benchmark_backoff();
+        let shrink = |n: usize| if backoff.reduce { (n / 4).max(1) } else { n };
+        let mut skipped = Vec::new();
+        let mut enabled = |flag: bool, name: &'static str| {
+            if flag && backoff.skip {
+                skipped.push(name);
+                false
+            } else {
+                flag
+            }
+        };
+        let run_scaling = enabled(compute_due && self.config.scaling_bench, "scaling");
+        let run_mem = enabled(compute_due && self.config.mem_bench, "mem");
+        let run_latency = enabled(compute_due && self.config.latency_bench, "latency");
+        let run_io = enabled(io_due && self.config.io_bench, "io");
+        let run_iops = enabled(io_due && self.config.iops_bench, "iops");
+        let run_fsync = enabled(io_due && self.config.fsync_bench, "fsync");
+        let run_metadata = enabled(io_due && self.config.metadata_bench, "metadata");
+        let run_targets = enabled(io_due && !self.config.io_targets.is_empty(), "targets");
+
+        let scaling = if run_scaling {
+            Some(benchmarks::benchmark_compute_scaling())
+        } else {
+            None
+        };
+
+        let mem_bandwidth = if run_mem {
+            Some(benchmarks::benchmark_memory_bandwidth(
+                shrink(self.config.mem_bench_size_mb),
+                self.config.mem_bench_threads,
+            ))
+        } else {
+            None
+        };
+
+        let mem_latency = if run_latency {
+            Some(benchmarks::benchmark_memory_latency())
+        } else {
+            None
+        };
+
+        let (io_read, io_write, sha_duration) = if run_io {
+            match benchmarks::benchmark_io(&self.config.test_file, shrink(self.config.file_size_mb))
+            {
                 Ok(IoBenchmarkResult {
                     read_mb_per_sec,
                     write_mb_per_sec,
@@ -173,6 +536,72 @@ impl App {
             (None, None, None)
         };
 
+        let random_io = if run_iops {
+            benchmarks::benchmark_random_io(
+                &self.config.test_file,
+                self.config.iops_queue_depth,
+                Duration::from_millis(shrink(self.config.iops_duration_ms as usize) as u64),
+            )
+            .ok()
+        } else {
+            None
+        };
+
+        let fsync = if run_fsync {
+            benchmarks::benchmark_fsync(&self.config.test_file, shrink(self.config.fsync_count))
+                .ok()
+        } else {
+            None
+        };
+
+        let metadata = if run_metadata {
+            benchmarks::benchmark_metadata(
+                &self.config.metadata_dir,
+                shrink(self.config.metadata_files),
+            )
+            .ok()
+        } else {
+            None
+        };
+
+        let targets = if run_targets {
+            self.benchmark_io_targets(shrink)
+        } else {
+            IoTargetResults::default()
+        };
+
+        let wakeup = if compute_due && self.config.wakeup_bench {
+            Some(benchmarks::benchmark_wakeup_latency(
+                Duration::from_micros(self.config.wakeup_period_us),
+                self.config.wakeup_samples,
+            ))
+        } else {
+            None
+        };
+
+        let ran: Vec<&str> = [
+            ("alloc", alloc_duration.is_some()),
+            ("compute", compute_duration.is_some()),
+            ("scaling", scaling.is_some()),
+            ("mem", mem_bandwidth.is_some()),
+            ("latency", mem_latency.is_some()),
+            ("wakeup", wakeup.is_some()),
+            ("io", io_read.is_some()),
+            ("iops", random_io.is_some()),
+            ("fsync", fsync.is_some()),
+            ("metadata", metadata.is_some()),
+            (
+                "targets",
+                !targets.read.is_empty()
+                    || !targets.iops_read.is_empty()
+                    || !targets.fsync_p99.is_empty()
+                    || !targets.meta_create.is_empty(),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, ran)| ran.then_some(name))
+        .collect();
+
         // === System stats from sysinfo ===
         let mem_total = self.sys.total_memory() / 1024 / 1024;
         let mem_used = self.sys.used_memory() / 1024 / 1024;
@@ -191,13 +620,17 @@ impl App {
         // === Stats from /proc ===
         let meminfo = collectors::read_meminfo();
         let cpu_stats = collectors::read_cpu_stats();
-        let disk_stats = collectors::read_disk_stats();
-        let net_stats = collectors::read_net_stats();
+        let disk_devices = collectors::read_disk_stats_by_device();
+        let net_interfaces = collectors::read_net_stats_by_interface();
+        let disk_stats = disk_devices.as_deref().map(DiskStats::total);
+        let net_stats = net_interfaces.as_deref().map(NetStats::total);
         let psi = collectors::read_psi();
         let temps = collectors::read_temperatures();
         let vm_stats = collectors::read_vmstat();
+        let throttle_stats = collectors::read_thermal_throttle();
         let (fd_allocated, fd_max) = collectors::read_fd_stats();
         let uptime = collectors::read_uptime();
+        let power = PowerInfo::collect();
 
         // === Collect SMART health (every 12 iterations = ~1 minute at 5s interval) ===
         self.smart_collection_counter += 1;
@@ -284,16 +717,121 @@ impl App {
             .zip(vm_stats.as_ref())
             .map(|(last, cur)| last.delta(cur));
 
+        let throttle_delta = self
+            .last_throttle_stats
+            .as_ref()
+            .zip(throttle_stats.as_ref())
+            .map(|(last, cur)| last.delta(cur));
+
+        let disk_device_deltas = device_deltas(
+            self.last_disk_devices.as_deref(),
+            disk_devices.as_deref(),
+            DiskStats::delta,
+        );
+        let net_interface_deltas = device_deltas(
+            self.last_net_interfaces.as_deref(),
+            net_interfaces.as_deref(),
+            NetStats::delta,
+        );
+
         // Build metrics struct
-        let metrics = Metrics {
+        let mut metrics = Metrics {
+            schema_version: SCHEMA_VERSION,
             timestamp,
             datetime,
 
             io_read_mb_per_sec: io_read,
             io_write_mb_per_sec: io_write,
             sha256_duration_ms: sha_duration,
+            iops_read: random_io.as_ref().map(|r| r.read_iops),
+            iops_read_p50_us: random_io.as_ref().map(|r| r.read_latency.p50_us),
+            iops_read_p99_us: random_io.as_ref().map(|r| r.read_latency.p99_us),
+            iops_read_p999_us: random_io.as_ref().map(|r| r.read_latency.p999_us),
+            iops_write: random_io.as_ref().map(|r| r.write_iops),
+            iops_write_p50_us: random_io.as_ref().map(|r| r.write_latency.p50_us),
+            iops_write_p99_us: random_io.as_ref().map(|r| r.write_latency.p99_us),
+            iops_write_p999_us: random_io.as_ref().map(|r| r.write_latency.p999_us),
+            iops_direct_io: random_io.as_ref().map(|r| r.direct_io),
+            fsync_p50_ms: fsync.as_ref().map(|r| r.fsync.p50_us / 1000.0),
+            fsync_p99_ms: fsync.as_ref().map(|r| r.fsync.p99_us / 1000.0),
+            fsync_max_ms: fsync.as_ref().map(|r| r.fsync.max_us / 1000.0),
+            fdatasync_p50_ms: fsync.as_ref().map(|r| r.fdatasync.p50_us / 1000.0),
+            fdatasync_p99_ms: fsync.as_ref().map(|r| r.fdatasync.p99_us / 1000.0),
+            fdatasync_max_ms: fsync.as_ref().map(|r| r.fdatasync.max_us / 1000.0),
+            meta_create_ops_per_sec: metadata.as_ref().map(|r| r.create_ops_per_sec),
+            meta_stat_ops_per_sec: metadata.as_ref().map(|r| r.stat_ops_per_sec),
+            meta_rename_ops_per_sec: metadata.as_ref().map(|r| r.rename_ops_per_sec),
+            meta_unlink_ops_per_sec: metadata.as_ref().map(|r| r.unlink_ops_per_sec),
+            wakeup_min_us: wakeup.as_ref().map(|r| r.min_us),
+            wakeup_avg_us: wakeup.as_ref().map(|r| r.avg_us),
+            wakeup_max_us: wakeup.as_ref().map(|r| r.max_us),
+            wakeup_p99_us: wakeup.as_ref().map(|r| r.p99_us),
+
+            benchmarks_ran: if ran.is_empty() {
+                None
+            } else {
+                Some(ran.join(","))
+            },
+            bench_backoff: if backoff.skip {
+                Some("skipped".into())
+            } else if backoff.reduce {
+                Some("reduced".into())
+            } else {
+                None
+            },
+            bench_backoff_reason: backoff.reason,
+            benchmarks_skipped: if skipped.is_empty() {
+                None
+            } else {
+                Some(skipped.join(","))
+            },
+
+            target_read_mb_per_sec: labeled_list(&targets.read),
+            target_write_mb_per_sec: labeled_list(&targets.write),
+            target_iops_read: labeled_list(&targets.iops_read),
+            target_iops_read_p99_us: labeled_list(&targets.iops_read_p99),
+            target_iops_write: labeled_list(&targets.iops_write),
+            target_fsync_p99_ms: labeled_list(&targets.fsync_p99),
+            target_meta_create_ops_per_sec: labeled_list(&targets.meta_create),
             memory_alloc_duration_ms: alloc_duration,
             compute_duration_ms: compute_duration,
+            core_compute_ms: scaling.as_ref().map(|r| {
+                r.per_core_ms
+                    .iter()
+                    .map(|(cpu, ms)| format!("{}:{:.1}", cpu, ms))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            core_compute_fastest_cpu: scaling.as_ref().and_then(|r| r.fastest()).map(|c| c.0),
+            core_compute_fastest_ms: scaling.as_ref().and_then(|r| r.fastest()).map(|c| c.1),
+            core_compute_slowest_cpu: scaling.as_ref().and_then(|r| r.slowest()).map(|c| c.0),
+            core_compute_slowest_ms: scaling.as_ref().and_then(|r| r.slowest()).map(|c| c.1),
+            all_core_compute_ms: scaling.as_ref().map(|r| r.all_cores_ms),
+            compute_scaling_efficiency: scaling.as_ref().and_then(|r| r.scaling_efficiency()),
+            slow_cores: scaling
+                .as_ref()
+                .map(|r| r.slow_cores(self.thresholds.slow_core_factor))
+                .filter(|cores| !cores.is_empty())
+                .map(|cores| {
+                    cores
+                        .iter()
+                        .map(|c| c.to_string())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                }),
+            mem_copy_gb_per_sec: mem_bandwidth.as_ref().map(|r| r.copy_gb_per_sec),
+            mem_scale_gb_per_sec: mem_bandwidth.as_ref().map(|r| r.scale_gb_per_sec),
+            mem_add_gb_per_sec: mem_bandwidth.as_ref().map(|r| r.add_gb_per_sec),
+            mem_triad_gb_per_sec: mem_bandwidth.as_ref().map(|r| r.triad_gb_per_sec),
+            mem_latency_l1_ns: mem_latency.as_ref().map(|r| r.l1_ns),
+            mem_latency_l2_ns: mem_latency.as_ref().map(|r| r.l2_ns),
+            mem_latency_llc_ns: mem_latency.as_ref().map(|r| r.llc_ns),
+            mem_latency_dram_ns: mem_latency.as_ref().map(|r| r.dram_ns),
+
+            // Filled in by apply_baseline()
+            baseline_pct: None,
+            baseline_worst_pct: None,
+            baseline_regressions: None,
 
             mem_total_mb: mem_total,
             mem_used_mb: mem_used,
@@ -371,6 +909,8 @@ impl App {
             disk_temps,
             disk_temp_source,
             disk_temp_max,
+            cpu_core_throttles: throttle_delta.as_ref().map(|t| t.core),
+            cpu_package_throttles: throttle_delta.as_ref().map(|t| t.package),
 
             context_switches: cpu_delta.as_ref().map(|s| s.context_switches).unwrap_or(0),
             interrupts: cpu_delta.as_ref().map(|s| s.interrupts).unwrap_or(0),
@@ -395,6 +935,14 @@ impl App {
 
             uptime_secs: uptime,
 
+            ac_online: power.ac_online,
+            battery_capacity_percent: power.battery_capacity_percent,
+            battery_status: power.battery_status,
+            battery_power_watts: power.battery_power_watts,
+            cpufreq_governor: power.cpufreq_governor,
+            cpufreq_epp: power.cpufreq_epp,
+            platform_profile: power.platform_profile,
+
             smart_available: smart.map(|s| s.available),
             smart_health_all_passed: smart.filter(|s| s.available).map(|s| s.all_healthy()),
             smart_reallocated_sectors_total: smart
@@ -426,28 +974,178 @@ impl App {
                 .filter(|s| s.available)
                 .map(|s| s.get_all_temps())
                 .unwrap_or_default(),
+
+            // Per-device
+            disk_devices: disk_device_deltas,
+            net_interfaces: net_interface_deltas,
         };
 
         // Store current stats for next delta calculation
         self.last_disk_stats = disk_stats;
         self.last_net_stats = net_stats;
+        self.last_disk_devices = disk_devices;
+        self.last_net_interfaces = net_interfaces;
         self.last_cpu_stats = cpu_stats;
         self.last_vm_stats = vm_stats;
+        self.last_throttle_stats = throttle_stats;
+
+        // Compare against the benchmark baseline
+        self.compare_baseline(&mut metrics);
+        self.new_output_errors.clear();
+        if let Some(e) = self.incident_error.take() {
+            let message = format!("{} (incidents are not saved this run)", e);
+            self.check_output("Incident file", Err(std::io::Error::new(e.kind(), message)));
+        }
 
         // Log to CSV
         self.log_metrics(&metrics)?;
 
+        // Update the baseline being recorded
+        let result = self.record_baseline(&metrics);
+        self.check_output("Baseline state file", result);
+
+        // Write to structured sinks
+        let results: Vec<_> = self
+            .sinks
+            .iter_mut()
+            .map(|sink| (sink.name(), sink.write(&metrics)))
+            .collect();
+        for (name, result) in results {
+            self.check_output(name, result);
+        }
+
+        if self.metrics_history.len() >= self.config.history_size {
+            self.metrics_history.pop_front();
+        }
+        self.metrics_history.push_back(metrics.clone());
+
+        // Track incidents and store in SQLite along with the current recommendations
+        let recommendations =
+            generate_recommendations(&metrics, &self.metrics_history, &self.thresholds);
+        self.incidents.update(metrics.timestamp, &recommendations);
+        if !self.incidents.changes().is_empty() {
+            let result = self.incidents.save();
+            self.check_output("Incident file", result);
+        }
+        if let Some(ref mut database) = self.database {
+            let result = database.record(&metrics, &recommendations, self.incidents.changes());
+            self.check_output("SQLite", result);
+        }
+
+        // Publish to the Prometheus exporter and/or textfile
+        if let Some(ref mut renderer) = self.prometheus_renderer {
+            let text = renderer.render(&metrics);
+            if let Some(ref exporter) = self.prometheus {
+                exporter.publish(&text);
+            }
+            if let Some(ref textfile) = self.textfile {
+                let result = textfile.write(&text);
+                self.check_output("Prometheus textfile", result);
+            }
+        }
+
         Ok(metrics)
     }
 
-    /// Log metrics to CSV file.
-    fn log_metrics(&mut self, metrics: &Metrics) -> std::io::Result<()> {
-        if let Some(ref mut writer) = self.csv_writer {
-            writer
-                .serialize(metrics)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-            writer.flush()?;
+    /// Decide whether the `--adaptive` policy should back off heavy benchmarks.
+    ///
+    /// Uses current PSI averages and the iowait share of the previous sample.
+    /// Warning-level stress shrinks the workloads, Critical skips them.
+    fn benchmark_backoff(&self) -> Backoff {
+        let mut backoff = Backoff::default();
+        if !self.config.adaptive {
+            return backoff;
+        }
+
+        let psi = collectors::read_psi();
+        let iowait_pct = self
+            .metrics_history
+            .back()
+            .and_then(Metrics::iowait_percent);
+
+        let checks = [
+            (
+                "io_pressure",
+                psi.io_some_avg10,
+                Thresholds::io_pressure_severity as fn(&Thresholds, f64) -> Severity,
+            ),
+            (
+                "mem_pressure",
+                psi.mem_some_avg10,
+                Thresholds::mem_pressure_severity,
+            ),
+            ("iowait", iowait_pct, Thresholds::iowait_severity),
+        ];
+
+        let mut reasons = Vec::new();
+        for (name, value, severity_fn) in checks {
+            let Some(value) = value else {
+                continue;
+            };
+            match severity_fn(&self.thresholds, value) {
+                Severity::Critical => backoff.skip = true,
+                Severity::Warning => backoff.reduce = true,
+                Severity::Normal => continue,
+            }
+            reasons.push(format!("{}={:.1}%", name, value));
+        }
+
+        if backoff.skip {
+            backoff.reduce = false;
+        }
+        if !reasons.is_empty() {
+            backoff.reason = Some(reasons.join(" "));
+        }
+        backoff
+    }
+
+    /// Add the sample to the baseline being recorded and save it.
+    ///
+    /// Samples whose benchmarks were reduced by the backoff are left out.
+    fn record_baseline(&mut self, metrics: &Metrics) -> std::io::Result<()> {
+        if metrics.benchmarks_reduced() {
+            return Ok(());
+        }
+        if let Some(ref mut recorder) = self.baseline_recorder {
+            recorder.add(metrics);
+            let recorded = recorder.to_baseline(&self.machine, &metrics.datetime);
+
+            // Re-read the store so baselines of other machines are preserved
+            let path = &self.baseline_path;
+            let mut store = BaselineStore::load(path)?;
+            store.baselines.insert(self.machine.key(), recorded);
+            store
+                .save(path)
+                .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
         }
         Ok(())
     }
+
+    /// Fill in the baseline comparison fields (unless recording one, or the
+    /// benchmarks were reduced by the backoff).
+    fn compare_baseline(&self, metrics: &mut Metrics) {
+        if metrics.benchmarks_reduced() {
+            return;
+        }
+        if let Some(ref baseline) = self.baseline {
+            let comparison = baseline.compare(metrics, self.thresholds.baseline_regression_factor);
+            let join = |items: &[(&str, f64)]| {
+                items
+                    .iter()
+                    .map(|(name, pct)| format!("{}:{:.0}", name, pct))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            };
+
+            metrics.baseline_worst_pct = comparison.worst().map(|(_, pct)| pct);
+            metrics.baseline_pct = Some(join(&comparison.percent)).filter(|s| !s.is_empty());
+            metrics.baseline_regressions =
+                Some(join(&comparison.regressions)).filter(|s| !s.is_empty());
+        }
+    }
+
+    /// Log metrics to CSV file.
+    fn log_metrics(&mut self, metrics: &Metrics) -> std::io::Result<()> {
+        self.csv_log.write(metrics)
+    }
 }
@@ -0,0 +1,326 @@
+//! Benchmark baselines and regression detection for slow-rs.
+//!
+//! Raw benchmark numbers are hard to interpret without a reference. This
+//! module records a baseline for each benchmark on a known-good run and
+//! later expresses results as a percentage of that baseline.
+//!
+//! Baselines are stored in a JSON state file, keyed by hostname and a
+//! hardware fingerprint (CPU model, core count, installed RAM), so one
+//! file can hold baselines for several machines and a hardware change
+//! does not get compared against a stale reference.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sysinfo::System;
+
+use crate::metrics::Metrics;
+
+/// A benchmark result tracked by baselines.
+pub struct BenchmarkMetric {
+    /// Metrics field name (also used as the key in the state file)
+    pub name: &'static str,
+    /// Extract the value from a sample (None if the benchmark didn't run)
+    pub value: fn(&Metrics) -> Option<f64>,
+    /// Whether larger values mean better performance (throughput vs. time)
+    pub higher_is_better: bool,
+}
+
+/// All benchmark results that can be compared against a baseline.
+pub const BENCHMARKS: &[BenchmarkMetric] = &[
+    BenchmarkMetric {
+        name: "io_read_mb_per_sec",
+        value: |m| m.io_read_mb_per_sec,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "io_write_mb_per_sec",
+        value: |m| m.io_write_mb_per_sec,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "sha256_duration_ms",
+        value: |m| m.sha256_duration_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "iops_read",
+        value: |m| m.iops_read,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "iops_write",
+        value: |m| m.iops_write,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "iops_read_p99_us",
+        value: |m| m.iops_read_p99_us,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "fsync_p99_ms",
+        value: |m| m.fsync_p99_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "meta_create_ops_per_sec",
+        value: |m| m.meta_create_ops_per_sec,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "meta_stat_ops_per_sec",
+        value: |m| m.meta_stat_ops_per_sec,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "wakeup_p99_us",
+        value: |m| m.wakeup_p99_us,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "memory_alloc_duration_ms",
+        value: |m| m.memory_alloc_duration_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "compute_duration_ms",
+        value: |m| m.compute_duration_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "core_compute_slowest_ms",
+        value: |m| m.core_compute_slowest_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "all_core_compute_ms",
+        value: |m| m.all_core_compute_ms,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "mem_triad_gb_per_sec",
+        value: |m| m.mem_triad_gb_per_sec,
+        higher_is_better: true,
+    },
+    BenchmarkMetric {
+        name: "mem_latency_llc_ns",
+        value: |m| m.mem_latency_llc_ns,
+        higher_is_better: false,
+    },
+    BenchmarkMetric {
+        name: "mem_latency_dram_ns",
+        value: |m| m.mem_latency_dram_ns,
+        higher_is_better: false,
+    },
+];
+
+/// Reference benchmark values for one machine.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Baseline {
+    /// Hostname the baseline was recorded on
+    pub hostname: String,
+    /// Hardware fingerprint the baseline was recorded on
+    pub fingerprint: String,
+    /// Human-readable hardware description (for the state file reader)
+    pub hardware: String,
+    /// When the baseline was last updated (ISO 8601)
+    pub recorded_at: String,
+    /// Number of samples the baseline values were derived from
+    pub samples: usize,
+    /// Median value per benchmark, keyed by [`BenchmarkMetric::name`]
+    pub values: BTreeMap<String, f64>,
+}
+
+/// Comparison of one sample against a baseline.
+#[derive(Clone, Debug, Default)]
+pub struct BaselineComparison {
+    /// Performance as % of baseline per benchmark (100 = same, <100 = slower)
+    pub percent: Vec<(&'static str, f64)>,
+    /// Benchmarks that regressed beyond the configured factor
+    pub regressions: Vec<(&'static str, f64)>,
+}
+
+impl BaselineComparison {
+    /// Worst (lowest) percentage across all compared benchmarks.
+    pub fn worst(&self) -> Option<(&'static str, f64)> {
+        self.percent
+            .iter()
+            .copied()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+impl Baseline {
+    /// Compare a sample against this baseline.
+    ///
+    /// A benchmark counts as regressed when its performance drops below
+    /// `1 / regression_factor` of the baseline (e.g. factor 1.5 → below 67%).
+    pub fn compare(&self, metrics: &Metrics, regression_factor: f64) -> BaselineComparison {
+        let mut comparison = BaselineComparison::default();
+
+        for bench in BENCHMARKS {
+            let (Some(value), Some(&base)) = ((bench.value)(metrics), self.values.get(bench.name))
+            else {
+                continue;
+            };
+            if value <= 0.0 || base <= 0.0 {
+                continue;
+            }
+
+            let pct = if bench.higher_is_better {
+                value / base * 100.0
+            } else {
+                base / value * 100.0
+            };
+            comparison.percent.push((bench.name, pct));
+            if pct < 100.0 / regression_factor {
+                comparison.regressions.push((bench.name, pct));
+            }
+        }
+
+        comparison
+    }
+}
+
+/// Accumulates benchmark samples during a `--record-baseline` run.
+#[derive(Default)]
+pub struct BaselineRecorder {
+    /// All values seen so far per benchmark
+    samples: BTreeMap<&'static str, Vec<f64>>,
+    /// Number of samples recorded
+    count: usize,
+}
+
+impl BaselineRecorder {
+    /// Add a sample's benchmark values.
+    pub fn add(&mut self, metrics: &Metrics) {
+        for bench in BENCHMARKS {
+            if let Some(value) = (bench.value)(metrics) {
+                self.samples.entry(bench.name).or_default().push(value);
+            }
+        }
+        self.count += 1;
+    }
+
+    /// Build a baseline from the medians of all samples recorded so far.
+    pub fn to_baseline(&self, identity: &MachineIdentity, recorded_at: &str) -> Baseline {
+        let values = self
+            .samples
+            .iter()
+            .map(|(name, values)| {
+                let mut sorted = values.clone();
+                sorted.sort_by(|a, b| a.total_cmp(b));
+                (name.to_string(), sorted[sorted.len() / 2])
+            })
+            .collect();
+
+        Baseline {
+            hostname: identity.hostname.clone(),
+            fingerprint: identity.fingerprint.clone(),
+            hardware: identity.hardware.clone(),
+            recorded_at: recorded_at.to_string(),
+            samples: self.count,
+            values,
+        }
+    }
+}
+
+/// Hostname and hardware fingerprint identifying a baseline.
+#[derive(Clone, Debug)]
+pub struct MachineIdentity {
+    /// System hostname
+    pub hostname: String,
+    /// Short hash of the hardware description
+    pub fingerprint: String,
+    /// Hardware description the fingerprint is derived from
+    pub hardware: String,
+}
+
+impl MachineIdentity {
+    /// Identify the current machine.
+    pub fn detect(sys: &System) -> Self {
+        let hostname = System::host_name().unwrap_or_else(|| "unknown".into());
+        let cpu_brand = sys
+            .cpus()
+            .first()
+            .map(|c| c.brand().trim().to_string())
+            .unwrap_or_default();
+        // Round RAM to whole GB so small reservations don't change the fingerprint
+        let mem_gb = (sys.total_memory() as f64 / 1024.0 / 1024.0 / 1024.0).round() as u64;
+        let hardware = format!("{} x{} {}GB", cpu_brand, sys.cpus().len(), mem_gb);
+
+        let digest = Sha256::digest(hardware.as_bytes());
+        let fingerprint = digest
+            .iter()
+            .take(8)
+            .map(|b| format!("{:02x}", b))
+            .collect();
+
+        Self {
+            hostname,
+            fingerprint,
+            hardware,
+        }
+    }
+
+    /// Key under which this machine's baseline is stored.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.hostname, self.fingerprint)
+    }
+}
+
+/// Baseline state file holding baselines for any number of machines.
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct BaselineStore {
+    /// Baselines keyed by [`MachineIdentity::key`]
+    pub baselines: BTreeMap<String, Baseline>,
+}
+
+impl BaselineStore {
+    /// Load the state file, returning an empty store if it doesn't exist.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|e| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    format!("{}: {}", path.display(), e),
+                )
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Save the state file atomically (write to a temp file, then rename).
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+}
+
+/// Default location of the baseline state file.
+///
+/// Uses `$XDG_STATE_HOME/slow-rs/baselines.json`, falling back to
+/// `~/.local/state/slow-rs/baselines.json`.
+pub fn default_path() -> PathBuf {
+    state_dir().join("baselines.json")
+}
+
+/// Directory for slow-rs state files (`$XDG_STATE_HOME/slow-rs`, falling
+/// back to `~/.local/state/slow-rs`).
+pub fn state_dir() -> PathBuf {
+    std::env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".local/state")))
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("slow-rs")
+}
@@ -7,8 +7,15 @@
 //! # Benchmarks
 //!
 //! - **Memory Allocation**: Allocates and touches 64MB of memory
+//! - **Memory Bandwidth**: STREAM-style copy/scale/add/triad kernels
+//! - **Memory Latency**: Randomised pointer chasing through L1/L2/LLC/DRAM
 //! - **Compute**: CPU-bound SHA256 hashing
+//! - **Compute Scaling**: The compute kernel pinned to each core, then on all cores at once
 //! - **I/O**: Disk read/write throughput
+//! - **Random I/O**: 4K random read/write IOPS and latency percentiles (O_DIRECT)
+//! - **fsync**: Latency of small writes followed by `fsync`/`fdatasync`
+//! - **Metadata**: create/stat/rename/unlink rates for small files
+//! - **Wakeup Latency**: How late a periodic timer-driven thread wakes up (cyclictest-like)
 //!
 //! These benchmarks help identify performance degradation that might
 //! not be visible in system statistics alone.
@@ -16,7 +23,7 @@
 use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::{Read, Write};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 /// Benchmark memory allocation performance.
 ///
@@ -71,7 +78,13 @@ pub fn benchmark_allocation() -> f64 {
 /// throttling or VM CPU contention.
 pub fn benchmark_compute() -> f64 {
     let start = Instant::now();
+    compute_kernel();
+    start.elapsed().as_secs_f64() * 1000.0
+}
 
+/// The SHA256 workload shared by [`benchmark_compute`] and
+/// [`benchmark_compute_scaling`].
+fn compute_kernel() {
     let mut hasher = Sha256::new();
 
     // Generate deterministic pseudo-random data
@@ -85,8 +98,483 @@ pub fn benchmark_compute() -> f64 {
     }
 
     let _ = std::hint::black_box(hasher.finalize());
+}
 
-    start.elapsed().as_secs_f64() * 1000.0
+/// Result of the multi-core compute scaling benchmark.
+pub struct ComputeScalingResult {
+    /// Per-core kernel time in milliseconds when run alone, as (cpu, ms).
+    /// CPUs the thread couldn't be pinned to (or whose run panicked) are
+    /// left out.
+    pub per_core_ms: Vec<(usize, f64)>,
+    /// Wall-clock time in milliseconds with the kernel on all cores at once
+    pub all_cores_ms: f64,
+    /// Number of CPUs in the all-core run
+    pub cpus: usize,
+    /// Number of physical cores those CPUs belong to (fewer than `cpus`
+    /// with SMT)
+    pub physical_cores: usize,
+}
+
+impl ComputeScalingResult {
+    /// Fastest core as (cpu, ms).
+    pub fn fastest(&self) -> Option<(usize, f64)> {
+        self.per_core_ms
+            .iter()
+            .copied()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Slowest core as (cpu, ms).
+    pub fn slowest(&self) -> Option<(usize, f64)> {
+        self.per_core_ms
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Median single-core time in milliseconds.
+    pub fn median_ms(&self) -> Option<f64> {
+        let mut times: Vec<f64> = self.per_core_ms.iter().map(|(_, ms)| *ms).collect();
+        if times.is_empty() {
+            return None;
+        }
+        times.sort_by(|a, b| a.total_cmp(b));
+        Some(times[times.len() / 2])
+    }
+
+    /// Scaling efficiency in percent, relative to the physical cores.
+    ///
+    /// With perfect scaling, running the kernel on every physical core at
+    /// once takes as long as running it on one core, giving 100%. SMT
+    /// siblings share a core, so the all-core run is expected to take
+    /// `cpus / physical_cores` times as long; above 100% means the siblings
+    /// added throughput.
+    pub fn scaling_efficiency(&self) -> Option<f64> {
+        if self.per_core_ms.is_empty() || self.all_cores_ms <= 0.0 || self.physical_cores == 0 {
+            return None;
+        }
+        let mean =
+            self.per_core_ms.iter().map(|(_, ms)| ms).sum::<f64>() / self.per_core_ms.len() as f64;
+        let smt_ways = self.cpus as f64 / self.physical_cores as f64;
+        Some(mean * smt_ways / self.all_cores_ms * 100.0)
+    }
+
+    /// CPUs whose single-core time exceeds the median by `factor` or more.
+    pub fn slow_cores(&self, factor: f64) -> Vec<usize> {
+        let median = match self.median_ms() {
+            Some(m) => m,
+            None => return Vec::new(),
+        };
+        self.per_core_ms
+            .iter()
+            .filter(|(_, ms)| *ms >= median * factor)
+            .map(|(cpu, _)| *cpu)
+            .collect()
+    }
+}
+
+/// Benchmark compute performance on every core individually and together.
+///
+/// Runs the [`benchmark_compute`] kernel pinned to each allowed CPU in
+/// turn, then on all of them simultaneously. A CPU the thread can't be
+/// pinned to is skipped rather than reported as per-core.
+///
+/// # What This Tests
+///
+/// - Individual cores being throttled (thermal, power capping)
+/// - vCPUs being stolen by the hypervisor
+/// - Shared-resource contention when all cores are busy
+///
+/// A core much slower than its siblings, or a low scaling efficiency,
+/// points at throttling or contention that a single-threaded benchmark
+/// cannot see.
+pub fn benchmark_compute_scaling() -> ComputeScalingResult {
+    let cpus = allowed_cpus();
+
+    let per_core_ms = cpus
+        .iter()
+        .filter_map(|&cpu| {
+            let ms = std::thread::spawn(move || {
+                if !pin_to_cpu(cpu) {
+                    return None;
+                }
+                let start = Instant::now();
+                compute_kernel();
+                Some(start.elapsed().as_secs_f64() * 1000.0)
+            })
+            .join()
+            .ok()
+            .flatten()?;
+            Some((cpu, ms))
+        })
+        .collect();
+
+    let all_cores_ms = time_secs(|| {
+        std::thread::scope(|s| {
+            for &cpu in &cpus {
+                s.spawn(move || {
+                    // Best effort: an unpinned thread still loads a core
+                    pin_to_cpu(cpu);
+                    compute_kernel();
+                });
+            }
+        })
+    }) * 1000.0;
+
+    ComputeScalingResult {
+        per_core_ms,
+        all_cores_ms,
+        cpus: cpus.len(),
+        physical_cores: physical_cores(&cpus),
+    }
+}
+
+/// Number of physical cores the given CPUs belong to.
+///
+/// Counts distinct (package, core) pairs from
+/// `/sys/devices/system/cpu/cpuN/topology`; a CPU without topology
+/// information counts as its own core.
+#[cfg(target_os = "linux")]
+fn physical_cores(cpus: &[usize]) -> usize {
+    let read = |cpu: usize, name: &str| {
+        std::fs::read_to_string(format!(
+            "/sys/devices/system/cpu/cpu{}/topology/{}",
+            cpu, name
+        ))
+        .ok()
+        .map(|s| s.trim().to_string())
+    };
+    let cores: std::collections::HashSet<_> = cpus
+        .iter()
+        .map(
+            |&cpu| match (read(cpu, "physical_package_id"), read(cpu, "core_id")) {
+                (Some(package), Some(core)) => (package, core),
+                _ => (format!("cpu{}", cpu), String::new()),
+            },
+        )
+        .collect();
+    cores.len()
+}
+
+/// Number of physical cores the given CPUs belong to (assumes no SMT).
+#[cfg(not(target_os = "linux"))]
+fn physical_cores(cpus: &[usize]) -> usize {
+    cpus.len()
+}
+
+/// CPUs this process is allowed to run on.
+#[cfg(target_os = "linux")]
+fn allowed_cpus() -> Vec<usize> {
+    // SAFETY: cpu_set_t is plain data; sched_getaffinity only writes into it
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) == 0 {
+            let cpus: Vec<usize> = (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| libc::CPU_ISSET(cpu, &set))
+                .collect();
+            if !cpus.is_empty() {
+                return cpus;
+            }
+        }
+    }
+    (0..std::thread::available_parallelism().map_or(1, |n| n.get())).collect()
+}
+
+/// CPUs this process is allowed to run on.
+#[cfg(not(target_os = "linux"))]
+fn allowed_cpus() -> Vec<usize> {
+    (0..std::thread::available_parallelism().map_or(1, |n| n.get())).collect()
+}
+
+/// Pin the calling thread to a single CPU. Returns false if that failed.
+#[cfg(target_os = "linux")]
+fn pin_to_cpu(cpu: usize) -> bool {
+    // SAFETY: cpu_set_t is plain data and pid 0 refers to the calling thread
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(cpu, &mut set);
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set) == 0
+    }
+}
+
+/// Pin the calling thread to a single CPU (always fails without affinity
+/// support).
+#[cfg(not(target_os = "linux"))]
+fn pin_to_cpu(_cpu: usize) -> bool {
+    false
+}
+
+/// Result of the STREAM-style memory bandwidth benchmark.
+///
+/// All values are in GB/s (10^9 bytes per second), counting bytes read
+/// and written per kernel the same way STREAM does.
+pub struct MemoryBandwidthResult {
+    /// `c[i] = a[i]`
+    pub copy_gb_per_sec: f64,
+    /// `b[i] = s * c[i]`
+    pub scale_gb_per_sec: f64,
+    /// `c[i] = a[i] + b[i]`
+    pub add_gb_per_sec: f64,
+    /// `a[i] = b[i] + s * c[i]`
+    pub triad_gb_per_sec: f64,
+}
+
+/// Benchmark memory bandwidth with STREAM-style kernels.
+///
+/// Allocates three `f64` arrays of `array_size_mb` each and runs the
+/// copy, scale, add and triad kernels over them, reporting the best of
+/// several repetitions. With `threads > 1` the arrays are split into
+/// contiguous chunks processed in parallel.
+///
+/// # Arguments
+///
+/// * `array_size_mb` - Size of each of the three arrays in MB
+/// * `threads` - Number of worker threads (1 = single-threaded)
+///
+/// # What This Tests
+///
+/// - Sustained DRAM throughput
+/// - Memory channel population (single- vs dual-channel)
+/// - Memory throttling due to DIMM temperature
+///
+/// The arrays should be much larger than the last-level cache, otherwise
+/// the result measures cache rather than DRAM bandwidth.
+pub fn benchmark_memory_bandwidth(array_size_mb: usize, threads: usize) -> MemoryBandwidthResult {
+    const REPETITIONS: usize = 3;
+    const SCALAR: f64 = 3.0;
+
+    let len = (array_size_mb.max(1) * 1024 * 1024) / std::mem::size_of::<f64>();
+    let threads = threads.max(1);
+    let chunk = (len + threads - 1) / threads;
+
+    let mut a = vec![1.0f64; len];
+    let mut b = vec![2.0f64; len];
+    let mut c = vec![0.0f64; len];
+
+    let mut best = [f64::INFINITY; 4];
+
+    for _ in 0..REPETITIONS {
+        // Copy
+        best[0] = best[0].min(time_secs(|| {
+            std::thread::scope(|s| {
+                for (dst, src) in c.chunks_mut(chunk).zip(a.chunks(chunk)) {
+                    s.spawn(move || dst.copy_from_slice(src));
+                }
+            })
+        }));
+
+        // Scale
+        best[1] = best[1].min(time_secs(|| {
+            std::thread::scope(|s| {
+                for (dst, src) in b.chunks_mut(chunk).zip(c.chunks(chunk)) {
+                    s.spawn(move || {
+                        for (d, x) in dst.iter_mut().zip(src) {
+                            *d = SCALAR * x;
+                        }
+                    });
+                }
+            })
+        }));
+
+        // Add
+        best[2] = best[2].min(time_secs(|| {
+            std::thread::scope(|s| {
+                for ((dst, x), y) in c
+                    .chunks_mut(chunk)
+                    .zip(a.chunks(chunk))
+                    .zip(b.chunks(chunk))
+                {
+                    s.spawn(move || {
+                        for ((d, x), y) in dst.iter_mut().zip(x).zip(y) {
+                            *d = x + y;
+                        }
+                    });
+                }
+            })
+        }));
+
+        // Triad
+        best[3] = best[3].min(time_secs(|| {
+            std::thread::scope(|s| {
+                for ((dst, x), y) in a
+                    .chunks_mut(chunk)
+                    .zip(b.chunks(chunk))
+                    .zip(c.chunks(chunk))
+                {
+                    s.spawn(move || {
+                        for ((d, x), y) in dst.iter_mut().zip(x).zip(y) {
+                            *d = x + SCALAR * y;
+                        }
+                    });
+                }
+            })
+        }));
+    }
+
+    std::hint::black_box((&a, &b, &c));
+
+    let bytes = (len * std::mem::size_of::<f64>()) as f64;
+    let gb_per_sec = |arrays: f64, secs: f64| arrays * bytes / secs / 1e9;
+
+    MemoryBandwidthResult {
+        copy_gb_per_sec: gb_per_sec(2.0, best[0]),
+        scale_gb_per_sec: gb_per_sec(2.0, best[1]),
+        add_gb_per_sec: gb_per_sec(3.0, best[2]),
+        triad_gb_per_sec: gb_per_sec(3.0, best[3]),
+    }
+}
+
+/// Result of the pointer-chasing memory latency benchmark.
+///
+/// Each value is the average load-to-use latency in nanoseconds for a
+/// working set sized to fit the given level of the cache hierarchy.
+pub struct MemoryLatencyResult {
+    /// Working set within L1 data cache
+    pub l1_ns: f64,
+    /// Working set within L2 cache
+    pub l2_ns: f64,
+    /// Working set within the last-level cache
+    pub llc_ns: f64,
+    /// Working set well beyond the last-level cache
+    pub dram_ns: f64,
+}
+
+/// Benchmark memory latency with randomised pointer chasing.
+///
+/// For each level of the cache hierarchy a working set is built as a
+/// single random cycle of cache-line-sized nodes, then walked so that
+/// every load depends on the previous one. This defeats the hardware
+/// prefetcher and measures true access latency.
+///
+/// Working-set sizes are derived from `/sys/devices/system/cpu/cpu0/cache`
+/// (half of each level, so it fits), with the DRAM set capped at 256MB.
+///
+/// # What This Tests
+///
+/// - Cache contention from noisy neighbours (L2/LLC latency rises)
+/// - NUMA-remote allocations (DRAM latency rises)
+/// - Memory controller or DIMM degradation
+pub fn benchmark_memory_latency() -> MemoryLatencyResult {
+    let (l1, l2, l3) = cache_sizes();
+
+    let l1_set = l1 / 2;
+    let l2_set = l2 / 2;
+    let llc_set = (l3 / 2).min(32 * 1024 * 1024);
+    let dram_set = (l3 * 2).clamp(64 * 1024 * 1024, 256 * 1024 * 1024);
+
+    MemoryLatencyResult {
+        l1_ns: pointer_chase_ns(l1_set),
+        l2_ns: pointer_chase_ns(l2_set),
+        llc_ns: pointer_chase_ns(llc_set),
+        dram_ns: pointer_chase_ns(dram_set),
+    }
+}
+
+/// Read L1d, L2 and L3 cache sizes in bytes, with typical fallbacks.
+fn cache_sizes() -> (usize, usize, usize) {
+    let mut l1 = 32 * 1024;
+    let mut l2 = 1024 * 1024;
+    let mut l3 = 16 * 1024 * 1024;
+
+    if let Ok(entries) = std::fs::read_dir("/sys/devices/system/cpu/cpu0/cache") {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let read = |name: &str| {
+                std::fs::read_to_string(path.join(name))
+                    .map(|s| s.trim().to_string())
+                    .unwrap_or_default()
+            };
+
+            if read("type") == "Instruction" {
+                continue;
+            }
+            let size = read("size");
+            let bytes = match size.strip_suffix('K') {
+                Some(kb) => kb.parse::<usize>().ok().map(|v| v * 1024),
+                None => match size.strip_suffix('M') {
+                    Some(mb) => mb.parse::<usize>().ok().map(|v| v * 1024 * 1024),
+                    None => size.parse().ok(),
+                },
+            };
+
+            if let Some(bytes) = bytes {
+                match read("level").as_str() {
+                    "1" => l1 = bytes,
+                    "2" => l2 = bytes,
+                    "3" => l3 = bytes,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    (l1, l2, l3)
+}
+
+/// Walk a random cyclic linked list of `working_set` bytes.
+///
+/// Returns the average nanoseconds per dependent load.
+fn pointer_chase_ns(working_set: usize) -> f64 {
+    const LINE: usize = 64 / std::mem::size_of::<usize>();
+    const ACCESSES: usize = 1_000_000;
+
+    let lines = (working_set / 64).max(2);
+    let mut chain = vec![0usize; lines * LINE];
+
+    // Sattolo's algorithm: a random permutation that forms a single cycle
+    let mut order: Vec<usize> = (0..lines).collect();
+    let mut rng = XorShift64::seeded();
+    for i in (1..lines).rev() {
+        let j = rng.next() as usize % i;
+        order.swap(i, j);
+    }
+    for (i, &next) in order.iter().enumerate() {
+        chain[i * LINE] = next * LINE;
+    }
+
+    // Warm up (populate caches/TLB) before timing
+    let mut idx = 0;
+    for _ in 0..lines.min(ACCESSES) {
+        idx = chain[idx];
+    }
+
+    let start = Instant::now();
+    for _ in 0..ACCESSES {
+        idx = chain[idx];
+    }
+    let elapsed = start.elapsed();
+    std::hint::black_box(idx);
+
+    elapsed.as_secs_f64() * 1e9 / ACCESSES as f64
+}
+
+/// Minimal xorshift PRNG for benchmark data layout (not cryptographic).
+struct XorShift64(u64);
+
+impl XorShift64 {
+    /// Seed from the current time.
+    fn seeded() -> Self {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Self(nanos | 1)
+    }
+
+    /// Next pseudo-random value.
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Time a closure, returning elapsed seconds.
+fn time_secs<F: FnOnce()>(f: F) -> f64 {
+    let start = Instant::now();
+    f();
+    start.elapsed().as_secs_f64()
 }
 
 /// Result of the I/O benchmark.
@@ -108,7 +596,7 @@ pub struct IoBenchmarkResult {
 /// # Arguments
 ///
 /// * `test_file` - Path to the test file for reading
-/// * `file_size_mb` - Size of the test file in MB
+/// * `file_size_mb` - MB to read from the test file (the write test uses a quarter)
 ///
 /// # Returns
 ///
@@ -128,13 +616,12 @@ pub fn benchmark_io(test_file: &str, file_size_mb: usize) -> std::io::Result<IoB
     // Try to drop caches for accurate measurement (requires root)
     let _ = std::fs::write("/proc/sys/vm/drop_caches", b"3");
 
-    let file_size = file_size_mb as f64;
-
-    // === Read benchmark with SHA256 ===
+    // === Read benchmark with SHA256 (at most file_size_mb) ===
     let start = Instant::now();
-    let mut file = File::open(test_file)?;
+    let mut file = File::open(test_file)?.take(file_size_mb as u64 * 1024 * 1024);
     let mut hasher = Sha256::new();
     let mut buffer = vec![0u8; 1024 * 1024]; // 1MB buffer
+    let mut bytes_read = 0usize;
 
     loop {
         let n = file.read(&mut buffer)?;
@@ -142,11 +629,12 @@ pub fn benchmark_io(test_file: &str, file_size_mb: usize) -> std::io::Result<IoB
             break;
         }
         hasher.update(&buffer[..n]);
+        bytes_read += n;
     }
     let _ = hasher.finalize();
 
     let read_duration = start.elapsed();
-    let read_mb_per_sec = file_size / read_duration.as_secs_f64();
+    let read_mb_per_sec = bytes_read as f64 / 1024.0 / 1024.0 / read_duration.as_secs_f64();
     let sha_duration_ms = read_duration.as_secs_f64() * 1000.0;
 
     // === Write benchmark ===
@@ -175,6 +663,432 @@ pub fn benchmark_io(test_file: &str, file_size_mb: usize) -> std::io::Result<IoB
     })
 }
 
+/// Latency distribution summary in microseconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LatencyStats {
+    /// Median latency
+    pub p50_us: f64,
+    /// 99th percentile latency
+    pub p99_us: f64,
+    /// 99.9th percentile latency
+    pub p999_us: f64,
+    /// Maximum latency
+    pub max_us: f64,
+}
+
+impl LatencyStats {
+    /// Summarise a set of latency samples (in microseconds).
+    pub fn from_samples(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+        samples.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            p50_us: percentile(&samples, 50.0),
+            p99_us: percentile(&samples, 99.0),
+            p999_us: percentile(&samples, 99.9),
+            max_us: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Nearest-rank percentile of an already sorted slice.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Maximum size of the scratch file written by [`benchmark_random_io`].
+pub const IOPS_SCRATCH_MB: usize = 64;
+
+/// Result of the random 4K I/O benchmark.
+pub struct RandomIoResult {
+    /// Random read operations per second
+    pub read_iops: f64,
+    /// Random read latency distribution
+    pub read_latency: LatencyStats,
+    /// Random write operations per second
+    pub write_iops: f64,
+    /// Random write latency distribution
+    pub write_latency: LatencyStats,
+    /// Whether O_DIRECT was used (false if the filesystem rejected it)
+    pub direct_io: bool,
+}
+
+/// Benchmark random 4K read/write IOPS and latency.
+///
+/// Issues 4KiB reads at random aligned offsets within the test file, then
+/// writes at random offsets within a scratch file next to it, for
+/// `duration` each. `queue_depth` workers submit I/O concurrently,
+/// emulating that many outstanding requests. The test file is opened
+/// read-only, so pointing `--test-file` at an existing file never modifies
+/// it. The scratch file (at most [`IOPS_SCRATCH_MB`]) is filled and synced
+/// before the write phase, so writes overwrite allocated blocks, and is
+/// removed afterwards.
+///
+/// The file is opened with `O_DIRECT` to bypass the page cache, so this
+/// measures the device even without root (unlike the cache drop used by
+/// [`benchmark_io`]). Filesystems that reject `O_DIRECT` (e.g. tmpfs)
+/// fall back to buffered I/O, reported via `direct_io`.
+///
+/// # What This Tests
+///
+/// - Small random I/O performance (what desktop applications do)
+/// - Latency tails (p99/p99.9), which make a system feel sluggish
+/// - Device behaviour under concurrent load at the given queue depth
+pub fn benchmark_random_io(
+    test_file: &str,
+    queue_depth: usize,
+    duration: Duration,
+) -> std::io::Result<RandomIoResult> {
+    const BLOCK: usize = 4096;
+
+    let (file, direct_io) = open_direct(test_file, false)?;
+    let blocks = file.metadata()?.len() / BLOCK as u64;
+    if blocks == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "test file smaller than one block",
+        ));
+    }
+    let (read_ops, read_samples) = run_random_io(&file, blocks, queue_depth, duration, false)?;
+
+    // Write phase on a scratch file slow-rs owns
+    let scratch_file = format!("{}.iops_test", test_file);
+    let scratch_blocks = blocks.min((IOPS_SCRATCH_MB * 1024 * 1024 / BLOCK) as u64);
+    let writes = (|| {
+        let mut f = File::create(&scratch_file)?;
+        let chunk = vec![0xABu8; BLOCK];
+        for _ in 0..scratch_blocks {
+            f.write_all(&chunk)?;
+        }
+        f.sync_all()?;
+        let (scratch, _) = open_direct(&scratch_file, true)?;
+        run_random_io(&scratch, scratch_blocks, queue_depth, duration, true)
+    })();
+    let _ = std::fs::remove_file(&scratch_file);
+    let (write_ops, write_samples) = writes?;
+
+    Ok(RandomIoResult {
+        read_iops: read_ops as f64 / duration.as_secs_f64(),
+        read_latency: LatencyStats::from_samples(read_samples),
+        write_iops: write_ops as f64 / duration.as_secs_f64(),
+        write_latency: LatencyStats::from_samples(write_samples),
+        direct_io,
+    })
+}
+
+/// Run one phase (read or write) of the random I/O benchmark.
+///
+/// Returns the number of completed operations and per-op latencies in µs.
+fn run_random_io(
+    file: &File,
+    blocks: u64,
+    queue_depth: usize,
+    duration: Duration,
+    write: bool,
+) -> std::io::Result<(usize, Vec<f64>)> {
+    use std::os::unix::fs::FileExt;
+
+    const BLOCK: usize = 4096;
+    let deadline = Instant::now() + duration;
+
+    let results: Vec<std::io::Result<Vec<f64>>> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..queue_depth.max(1))
+            .map(|_| {
+                s.spawn(move || {
+                    // O_DIRECT requires a block-aligned buffer
+                    let mut raw = vec![0xABu8; BLOCK * 2];
+                    let offset = raw.as_ptr().align_offset(BLOCK);
+                    let buf = &mut raw[offset..offset + BLOCK];
+
+                    let mut rng = XorShift64::seeded();
+                    let mut samples = Vec::new();
+                    while Instant::now() < deadline {
+                        let pos = (rng.next() % blocks) * BLOCK as u64;
+                        let start = Instant::now();
+                        if write {
+                            file.write_all_at(buf, pos)?;
+                        } else {
+                            file.read_exact_at(buf, pos)?;
+                        }
+                        samples.push(start.elapsed().as_secs_f64() * 1e6);
+                    }
+                    Ok(samples)
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .map(|w| w.join().unwrap_or_else(|_| Ok(Vec::new())))
+            .collect()
+    });
+
+    let mut all = Vec::new();
+    for samples in results {
+        all.extend(samples?);
+    }
+    Ok((all.len(), all))
+}
+
+/// Result of the fsync latency benchmark.
+pub struct FsyncResult {
+    /// Latency of 4K write + `fsync` (data and metadata)
+    pub fsync: LatencyStats,
+    /// Latency of 4K write + `fdatasync` (data only)
+    pub fdatasync: LatencyStats,
+}
+
+/// Benchmark fsync/fdatasync latency.
+///
+/// Appends `count` 4K blocks to a scratch file next to the test file,
+/// syncing after each write, first with `fsync` and then with
+/// `fdatasync`. This is the pattern used by databases, git and package
+/// managers, which block until the sync returns.
+///
+/// # What This Tests
+///
+/// - Journal commit latency of the filesystem
+/// - Writeback congestion (sync waits behind queued dirty data)
+/// - SSD controller stalls (garbage collection, cache flushes)
+pub fn benchmark_fsync(test_file: &str, count: usize) -> std::io::Result<FsyncResult> {
+    let sync_test_file = format!("{}.fsync_test", test_file);
+    let block = vec![0xEFu8; 4096];
+
+    let run = |data_only: bool| -> std::io::Result<LatencyStats> {
+        let mut f = File::create(&sync_test_file)?;
+        let mut samples = Vec::with_capacity(count);
+        for _ in 0..count.max(1) {
+            let start = Instant::now();
+            f.write_all(&block)?;
+            if data_only {
+                f.sync_data()?;
+            } else {
+                f.sync_all()?;
+            }
+            samples.push(start.elapsed().as_secs_f64() * 1e6);
+        }
+        Ok(LatencyStats::from_samples(samples))
+    };
+
+    let result = run(false).and_then(|fsync| {
+        Ok(FsyncResult {
+            fsync,
+            fdatasync: run(true)?,
+        })
+    });
+
+    // Cleanup
+    let _ = std::fs::remove_file(&sync_test_file);
+
+    result
+}
+
+/// Result of the filesystem metadata benchmark (operations per second).
+pub struct MetadataResult {
+    /// File creations per second (create + small write + close)
+    pub create_ops_per_sec: f64,
+    /// `stat` calls per second
+    pub stat_ops_per_sec: f64,
+    /// Renames per second
+    pub rename_ops_per_sec: f64,
+    /// Unlinks per second
+    pub unlink_ops_per_sec: f64,
+}
+
+/// Benchmark filesystem metadata operations.
+///
+/// Creates a private temporary directory under `base_dir`, then creates,
+/// stats, renames and unlinks `file_count` small files in it, timing each
+/// phase separately. The directory is removed afterwards.
+///
+/// # What This Tests
+///
+/// - Directory/inode operation latency (what `ls`, `git status` and
+///   builds are bound by)
+/// - Slow network filesystems (NFS/SMB round trips)
+/// - Overloaded filesystem journals and dentry/inode cache pressure
+pub fn benchmark_metadata(base_dir: &str, file_count: usize) -> std::io::Result<MetadataResult> {
+    let dir = std::path::Path::new(base_dir).join(format!("slow-rs-meta-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+
+    let count = file_count.max(1);
+    let names: Vec<_> = (0..count).map(|i| dir.join(format!("f{}", i))).collect();
+    let renamed: Vec<_> = (0..count).map(|i| dir.join(format!("r{}", i))).collect();
+
+    let phases = (|| -> std::io::Result<[f64; 4]> {
+        let create = time_secs_io(|| {
+            for name in &names {
+                File::create(name)?.write_all(b"slow-rs")?;
+            }
+            Ok(())
+        })?;
+        let stat = time_secs_io(|| {
+            for name in &names {
+                std::hint::black_box(std::fs::metadata(name)?);
+            }
+            Ok(())
+        })?;
+        let rename = time_secs_io(|| {
+            for (from, to) in names.iter().zip(&renamed) {
+                std::fs::rename(from, to)?;
+            }
+            Ok(())
+        })?;
+        let unlink = time_secs_io(|| {
+            for name in &renamed {
+                std::fs::remove_file(name)?;
+            }
+            Ok(())
+        })?;
+        Ok([create, stat, rename, unlink])
+    })();
+
+    // Cleanup (also after a failed phase)
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let [create, stat, rename, unlink] = phases?;
+    let rate = |secs: f64| count as f64 / secs.max(1e-9);
+
+    Ok(MetadataResult {
+        create_ops_per_sec: rate(create),
+        stat_ops_per_sec: rate(stat),
+        rename_ops_per_sec: rate(rename),
+        unlink_ops_per_sec: rate(unlink),
+    })
+}
+
+/// Time a fallible closure, returning elapsed seconds.
+fn time_secs_io<F: FnOnce() -> std::io::Result<()>>(f: F) -> std::io::Result<f64> {
+    let start = Instant::now();
+    f()?;
+    Ok(start.elapsed().as_secs_f64())
+}
+
+/// Result of the scheduler wakeup latency benchmark (microseconds).
+pub struct WakeupLatencyResult {
+    /// Smallest observed wakeup delay
+    pub min_us: f64,
+    /// Average wakeup delay
+    pub avg_us: f64,
+    /// Largest observed wakeup delay
+    pub max_us: f64,
+    /// 99th percentile wakeup delay
+    pub p99_us: f64,
+}
+
+/// Benchmark scheduler wakeup latency (cyclictest-like).
+///
+/// A dedicated thread sleeps until absolute deadlines `period` apart on
+/// the monotonic clock, `samples` times, recording how late each wakeup
+/// is. On Linux this uses `clock_nanosleep(TIMER_ABSTIME)` so the timer
+/// itself does not drift.
+///
+/// # What This Tests
+///
+/// - How long runnable tasks wait for a CPU (PSI says *that* they wait,
+///   this says *for how long*)
+/// - Latency spikes from IRQ storms, SMIs or long non-preemptible
+///   kernel sections ("the mouse cursor stutters")
+pub fn benchmark_wakeup_latency(period: Duration, samples: usize) -> WakeupLatencyResult {
+    let delays = std::thread::spawn(move || {
+        let mut delays = Vec::with_capacity(samples);
+        let mut deadline = Instant::now();
+        for _ in 0..samples.max(1) {
+            deadline += period;
+            sleep_until(deadline);
+            let late = Instant::now().saturating_duration_since(deadline);
+            delays.push(late.as_secs_f64() * 1e6);
+        }
+        delays
+    })
+    .join()
+    .unwrap_or_default();
+
+    let stats = LatencyStats::from_samples(delays.clone());
+    WakeupLatencyResult {
+        min_us: delays.iter().copied().fold(f64::INFINITY, f64::min),
+        avg_us: delays.iter().sum::<f64>() / delays.len().max(1) as f64,
+        max_us: stats.max_us,
+        p99_us: stats.p99_us,
+    }
+}
+
+/// Sleep until an absolute deadline on the monotonic clock.
+#[cfg(target_os = "linux")]
+fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline <= now {
+        return;
+    }
+
+    // Translate the Instant deadline into an absolute CLOCK_MONOTONIC time
+    // SAFETY: clock_gettime/clock_nanosleep only read/write the timespecs passed in
+    unsafe {
+        let mut ts: libc::timespec = std::mem::zeroed();
+        libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts);
+        let remaining = deadline - now;
+        let mut nanos = ts.tv_nsec as u64 + remaining.subsec_nanos() as u64;
+        ts.tv_sec += remaining.as_secs() as libc::time_t + (nanos / 1_000_000_000) as libc::time_t;
+        nanos %= 1_000_000_000;
+        ts.tv_nsec = nanos as _;
+
+        while libc::clock_nanosleep(
+            libc::CLOCK_MONOTONIC,
+            libc::TIMER_ABSTIME,
+            &ts,
+            std::ptr::null_mut(),
+        ) == libc::EINTR
+        {}
+    }
+}
+
+/// Sleep until a deadline (relative sleep on non-Linux platforms).
+#[cfg(not(target_os = "linux"))]
+fn sleep_until(deadline: Instant) {
+    let now = Instant::now();
+    if deadline > now {
+        std::thread::sleep(deadline - now);
+    }
+}
+
+/// Open a file for reading (and writing if `write`) with O_DIRECT, falling
+/// back to buffered I/O.
+///
+/// Returns the file and whether O_DIRECT is in effect.
+#[cfg(target_os = "linux")]
+fn open_direct(path: &str, write: bool) -> std::io::Result<(File, bool)> {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    match std::fs::OpenOptions::new()
+        .read(true)
+        .write(write)
+        .custom_flags(libc::O_DIRECT)
+        .open(path)
+    {
+        Ok(f) => Ok((f, true)),
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => Ok((
+            std::fs::OpenOptions::new()
+                .read(true)
+                .write(write)
+                .open(path)?,
+            false,
+        )),
+        Err(e) => Err(e),
+    }
+}
+
+/// Open a file for reading (and writing if `write`); O_DIRECT is
+/// Linux-only.
+#[cfg(not(target_os = "linux"))]
+fn open_direct(path: &str, write: bool) -> std::io::Result<(File, bool)> {
+    let f = std::fs::OpenOptions::new()
+        .read(true)
+        .write(write)
+        .open(path)?;
+    Ok((f, false))
+}
+
 /// Create the test file for I/O benchmarks.
 ///
 /// Creates a file filled with a repeating pattern. The pattern helps
@@ -14,6 +14,10 @@
 //! - `/proc/uptime` - System uptime
 //! - `/proc/sys/fs/file-nr` - File descriptor usage
 //! - `/sys/class/hwmon/*/temp*` - Hardware temperatures
+//! - `/sys/devices/system/cpu/cpu*/thermal_throttle/*_throttle_count` -
+//!   Thermal throttle events (x86)
+
+use serde::{Deserialize, Serialize};
 
 /// Detailed memory information from `/proc/meminfo`.
 #[derive(Default, Clone, Debug)]
@@ -68,7 +72,7 @@ pub struct CpuStats {
 }
 
 /// Disk I/O statistics from `/proc/diskstats`.
-#[derive(Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct DiskStats {
     /// Reads completed successfully
     pub reads_completed: u64,
@@ -95,7 +99,7 @@ pub struct DiskStats {
 }
 
 /// Network interface statistics from `/proc/net/dev`.
-#[derive(Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct NetStats {
     /// Bytes received
     pub rx_bytes: u64,
@@ -128,6 +132,16 @@ pub struct VmStats {
     pub pswpout: u64,
 }
 
+/// Thermal throttle event counters from
+/// `/sys/devices/system/cpu/cpu*/thermal_throttle/`.
+#[derive(Clone, Debug, Default)]
+pub struct ThrottleStats {
+    /// Times a core was throttled, summed over physical cores
+    pub core: u64,
+    /// Times a package was throttled, summed over packages
+    pub package: u64,
+}
+
 /// Pressure Stall Information from `/proc/pressure/*`.
 #[derive(Default, Clone, Debug)]
 pub struct PsiInfo {
@@ -246,12 +260,13 @@ pub fn read_cpu_stats() -> Option<CpuStats> {
     Some(stats)
 }
 
-/// Read disk I/O statistics from `/proc/diskstats`.
+/// Read per-device disk I/O statistics from `/proc/diskstats`.
 ///
-/// Only counts whole-disk devices (sda, nvme0n1, vda, xvda), not partitions.
-pub fn read_disk_stats() -> Option<DiskStats> {
+/// Returns cumulative counters for whole-disk devices (sda, nvme0n1, vda,
+/// xvda) only, not partitions, keyed by device name.
+pub fn read_disk_stats_by_device() -> Option<Vec<(String, DiskStats)>> {
     let content = std::fs::read_to_string("/proc/diskstats").ok()?;
-    let mut stats = DiskStats::default();
+    let mut devices = Vec::new();
 
     for line in content.lines() {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -267,29 +282,35 @@ pub fn read_disk_stats() -> Option<DiskStats> {
             || (device.starts_with("xvd") && device.len() == 4);
 
         if is_disk {
-            stats.reads_completed += parts[3].parse::<u64>().unwrap_or(0);
-            stats.reads_merged += parts[4].parse::<u64>().unwrap_or(0);
-            stats.sectors_read += parts[5].parse::<u64>().unwrap_or(0);
-            stats.read_time_ms += parts[6].parse::<u64>().unwrap_or(0);
-            stats.writes_completed += parts[7].parse::<u64>().unwrap_or(0);
-            stats.writes_merged += parts[8].parse::<u64>().unwrap_or(0);
-            stats.sectors_written += parts[9].parse::<u64>().unwrap_or(0);
-            stats.write_time_ms += parts[10].parse::<u64>().unwrap_or(0);
-            stats.io_in_progress += parts[11].parse::<u64>().unwrap_or(0);
-            stats.io_time_ms += parts[12].parse::<u64>().unwrap_or(0);
-            stats.weighted_io_time_ms += parts[13].parse::<u64>().unwrap_or(0);
+            let field = |idx: usize| parts[idx].parse::<u64>().unwrap_or(0);
+            devices.push((
+                device.to_string(),
+                DiskStats {
+                    reads_completed: field(3),
+                    reads_merged: field(4),
+                    sectors_read: field(5),
+                    read_time_ms: field(6),
+                    writes_completed: field(7),
+                    writes_merged: field(8),
+                    sectors_written: field(9),
+                    write_time_ms: field(10),
+                    io_in_progress: field(11),
+                    io_time_ms: field(12),
+                    weighted_io_time_ms: field(13),
+                },
+            ));
         }
     }
 
-    Some(stats)
+    Some(devices)
 }
 
-/// Read network statistics from `/proc/net/dev`.
+/// Read per-interface network statistics from `/proc/net/dev`.
 ///
-/// Aggregates stats across all interfaces except loopback.
-pub fn read_net_stats() -> Option<NetStats> {
+/// Returns cumulative counters for every interface except loopback.
+pub fn read_net_stats_by_interface() -> Option<Vec<(String, NetStats)>> {
     let content = std::fs::read_to_string("/proc/net/dev").ok()?;
-    let mut stats = NetStats::default();
+    let mut interfaces = Vec::new();
 
     for line in content.lines().skip(2) {
         let parts: Vec<&str> = line.split_whitespace().collect();
@@ -303,15 +324,21 @@ pub fn read_net_stats() -> Option<NetStats> {
             continue;
         }
 
-        stats.rx_bytes += parts[1].parse::<u64>().unwrap_or(0);
-        stats.rx_packets += parts[2].parse::<u64>().unwrap_or(0);
-        stats.rx_errors += parts[3].parse::<u64>().unwrap_or(0);
-        stats.tx_bytes += parts[9].parse::<u64>().unwrap_or(0);
-        stats.tx_packets += parts[10].parse::<u64>().unwrap_or(0);
-        stats.tx_errors += parts[11].parse::<u64>().unwrap_or(0);
+        let field = |idx: usize| parts[idx].parse::<u64>().unwrap_or(0);
+        interfaces.push((
+            iface.to_string(),
+            NetStats {
+                rx_bytes: field(1),
+                rx_packets: field(2),
+                rx_errors: field(3),
+                tx_bytes: field(9),
+                tx_packets: field(10),
+                tx_errors: field(11),
+            },
+        ));
     }
 
-    Some(stats)
+    Some(interfaces)
 }
 
 /// Read Pressure Stall Information from `/proc/pressure/*`.
@@ -495,6 +522,51 @@ pub fn read_vmstat() -> Option<VmStats> {
     Some(stats)
 }
 
+/// Read the thermal throttle counters of all CPUs.
+///
+/// Every CPU reports the counter of its core and of its package, so SMT
+/// siblings are counted once per (package, core) and each package once.
+/// Returns None where the kernel doesn't expose them (non-x86, most VMs).
+pub fn read_thermal_throttle() -> Option<ThrottleStats> {
+    use std::collections::BTreeMap;
+
+    let read = |path: std::path::PathBuf| -> Option<String> {
+        Some(std::fs::read_to_string(path).ok()?.trim().to_string())
+    };
+    let mut cores: BTreeMap<(String, String), u64> = BTreeMap::new();
+    let mut packages: BTreeMap<String, u64> = BTreeMap::new();
+
+    for entry in std::fs::read_dir("/sys/devices/system/cpu").ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name
+            .strip_prefix("cpu")
+            .is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()))
+        {
+            continue;
+        }
+        let path = entry.path();
+        let throttle = path.join("thermal_throttle");
+        let count = |file: &str| read(throttle.join(file)).and_then(|s| s.parse::<u64>().ok());
+        let package_id = read(path.join("topology/physical_package_id")).unwrap_or_default();
+        let core_id = read(path.join("topology/core_id")).unwrap_or(name);
+
+        if let Some(n) = count("core_throttle_count") {
+            cores.insert((package_id.clone(), core_id), n);
+        }
+        if let Some(n) = count("package_throttle_count") {
+            packages.insert(package_id, n);
+        }
+    }
+
+    if cores.is_empty() && packages.is_empty() {
+        return None;
+    }
+    Some(ThrottleStats {
+        core: cores.values().sum(),
+        package: packages.values().sum(),
+    })
+}
+
 /// Read file descriptor statistics from `/proc/sys/fs/file-nr`.
 ///
 /// Returns (allocated, max).
@@ -521,6 +593,30 @@ pub fn read_uptime() -> f64 {
 }
 
 impl DiskStats {
+    /// Sum the counters of all devices.
+    pub fn total(devices: &[(String, Self)]) -> Self {
+        let mut total = Self::default();
+        for (_, device) in devices {
+            total.add(device);
+        }
+        total
+    }
+
+    /// Add another device's counters to this one.
+    fn add(&mut self, other: &Self) {
+        self.reads_completed += other.reads_completed;
+        self.reads_merged += other.reads_merged;
+        self.sectors_read += other.sectors_read;
+        self.read_time_ms += other.read_time_ms;
+        self.writes_completed += other.writes_completed;
+        self.writes_merged += other.writes_merged;
+        self.sectors_written += other.sectors_written;
+        self.write_time_ms += other.write_time_ms;
+        self.io_in_progress += other.io_in_progress;
+        self.io_time_ms += other.io_time_ms;
+        self.weighted_io_time_ms += other.weighted_io_time_ms;
+    }
+
     /// Calculate the difference between two disk stats snapshots.
     pub fn delta(&self, other: &Self) -> Self {
         Self {
@@ -562,6 +658,25 @@ impl CpuStats {
 }
 
 impl NetStats {
+    /// Sum the counters of all interfaces.
+    pub fn total(interfaces: &[(String, Self)]) -> Self {
+        let mut total = Self::default();
+        for (_, iface) in interfaces {
+            total.add(iface);
+        }
+        total
+    }
+
+    /// Add another interface's counters to this one.
+    fn add(&mut self, other: &Self) {
+        self.rx_bytes += other.rx_bytes;
+        self.tx_bytes += other.tx_bytes;
+        self.rx_packets += other.rx_packets;
+        self.tx_packets += other.tx_packets;
+        self.rx_errors += other.rx_errors;
+        self.tx_errors += other.tx_errors;
+    }
+
     /// Calculate the difference between two network stats snapshots.
     pub fn delta(&self, other: &Self) -> Self {
         Self {
@@ -575,6 +690,16 @@ impl NetStats {
     }
 }
 
+impl ThrottleStats {
+    /// Calculate the difference between two throttle counter snapshots.
+    pub fn delta(&self, other: &Self) -> Self {
+        Self {
+            core: other.core.saturating_sub(self.core),
+            package: other.package.saturating_sub(self.package),
+        }
+    }
+}
+
 impl VmStats {
     /// Calculate the difference between two VM stats snapshots.
     pub fn delta(&self, other: &Self) -> Self {
@@ -0,0 +1,436 @@
+//! Before/after comparison of two recordings for slow-rs.
+//!
+//! `slow-rs compare a.csv b.csv` loads two recordings (anything
+//! [`crate::replay`] can load; columns are matched by name, so different
+//! schema versions line up) and compares the distribution of each
+//! benchmark, PSI, temperature and disk latency metric.
+//!
+//! A metric counts as changed when Welch's t-test finds the means differ
+//! (p below `--alpha`) and the effect size, Cohen's d with the pooled
+//! standard deviation, is at least `--min-effect`. Requiring both keeps
+//! long recordings, where tiny differences become statistically
+//! significant, from flagging everything.
+
+use serde::Serialize;
+
+use crate::config::{CompareArgs, CompareFormat};
+use crate::metrics::{format_value, Metrics};
+use crate::replay;
+
+/// Which direction of change is an improvement.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Better {
+    /// Larger values are better (throughput)
+    Higher,
+    /// Smaller values are better (durations, latencies, pressure, temperatures)
+    Lower,
+}
+
+/// A compared metric: name, unit, which direction is better and value extractor.
+type Compared = (
+    &'static str,
+    &'static str,
+    Better,
+    fn(&Metrics) -> Option<f64>,
+);
+
+/// Metrics compared between the recordings.
+const COMPARED: &[Compared] = &[
+    // Benchmarks
+    ("io_read", "MB/s", Better::Higher, |m| {
+        m.unreduced(m.io_read_mb_per_sec)
+    }),
+    ("io_write", "MB/s", Better::Higher, |m| {
+        m.unreduced(m.io_write_mb_per_sec)
+    }),
+    ("sha256", "ms", Better::Lower, |m| {
+        m.unreduced(m.sha256_duration_ms)
+    }),
+    ("iops_read", "IOPS", Better::Higher, |m| {
+        m.unreduced(m.iops_read)
+    }),
+    ("iops_write", "IOPS", Better::Higher, |m| {
+        m.unreduced(m.iops_write)
+    }),
+    ("meta_create", "ops/s", Better::Higher, |m| {
+        m.unreduced(m.meta_create_ops_per_sec)
+    }),
+    ("compute", "ms", Better::Lower, |m| m.compute_duration_ms),
+    ("memory_alloc", "ms", Better::Lower, |m| {
+        m.memory_alloc_duration_ms
+    }),
+    ("all_core_compute", "ms", Better::Lower, |m| {
+        m.all_core_compute_ms
+    }),
+    ("scaling_efficiency", "%", Better::Higher, |m| {
+        m.compute_scaling_efficiency
+    }),
+    ("mem_triad", "GB/s", Better::Higher, |m| {
+        m.unreduced(m.mem_triad_gb_per_sec)
+    }),
+    ("mem_latency_dram", "ns", Better::Lower, |m| {
+        m.mem_latency_dram_ns
+    }),
+    ("wakeup_p99", "µs", Better::Lower, |m| m.wakeup_p99_us),
+    // Disk latency
+    ("iops_read_p99", "µs", Better::Lower, |m| {
+        m.unreduced(m.iops_read_p99_us)
+    }),
+    ("iops_write_p99", "µs", Better::Lower, |m| {
+        m.unreduced(m.iops_write_p99_us)
+    }),
+    ("fsync_p99", "ms", Better::Lower, |m| {
+        m.unreduced(m.fsync_p99_ms)
+    }),
+    ("fdatasync_p99", "ms", Better::Lower, |m| {
+        m.unreduced(m.fdatasync_p99_ms)
+    }),
+    ("disk_read_await", "ms", Better::Lower, |m| {
+        (m.disk_reads_completed > 0)
+            .then(|| m.disk_read_time_ms as f64 / m.disk_reads_completed as f64)
+    }),
+    ("disk_write_await", "ms", Better::Lower, |m| {
+        (m.disk_writes_completed > 0)
+            .then(|| m.disk_write_time_ms as f64 / m.disk_writes_completed as f64)
+    }),
+    // Pressure stall information
+    ("cpu_pressure_some", "%", Better::Lower, |m| {
+        m.cpu_pressure_some_avg10
+    }),
+    ("mem_pressure_some", "%", Better::Lower, |m| {
+        m.mem_pressure_some_avg10
+    }),
+    ("mem_pressure_full", "%", Better::Lower, |m| {
+        m.mem_pressure_full_avg10
+    }),
+    ("io_pressure_some", "%", Better::Lower, |m| {
+        m.io_pressure_some_avg10
+    }),
+    ("io_pressure_full", "%", Better::Lower, |m| {
+        m.io_pressure_full_avg10
+    }),
+    // Temperatures
+    ("cpu_temp", "°C", Better::Lower, |m| m.cpu_temp_celsius),
+    ("dimm_temp_max", "°C", Better::Lower, |m| m.dimm_temp_max),
+    ("disk_temp_max", "°C", Better::Lower, |m| m.disk_temp_max),
+];
+
+/// Mean, spread and size of one metric in one recording.
+#[derive(Serialize)]
+struct Distribution {
+    /// Number of samples with a value
+    count: usize,
+    /// Mean value
+    mean: f64,
+    /// Sample standard deviation
+    std_dev: f64,
+    /// Median value
+    median: f64,
+}
+
+impl Distribution {
+    /// Summarise values (None if there are fewer than two).
+    fn from_values(mut values: Vec<f64>) -> Option<Self> {
+        if values.len() < 2 {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        let mid = values.len() / 2;
+        let median = if values.len() % 2 == 0 {
+            (values[mid - 1] + values[mid]) / 2.0
+        } else {
+            values[mid]
+        };
+        Some(Self {
+            count: values.len(),
+            mean,
+            std_dev: variance.sqrt(),
+            median,
+        })
+    }
+
+    /// Variance of the mean (squared standard error).
+    fn mean_variance(&self) -> f64 {
+        self.std_dev.powi(2) / self.count as f64
+    }
+}
+
+/// Comparison of one metric.
+#[derive(Serialize)]
+struct MetricChange {
+    /// Metric name
+    metric: &'static str,
+    /// Unit of the values
+    unit: &'static str,
+    /// Distribution in the first recording
+    before: Distribution,
+    /// Distribution in the second recording
+    after: Distribution,
+    /// Change of the mean in percent (None if the first mean is 0)
+    change_percent: Option<f64>,
+    /// Cohen's d (None if both recordings are constant but differ)
+    cohens_d: Option<f64>,
+    /// Two-sided p-value of Welch's t-test
+    p_value: f64,
+    /// "better", "worse" or "unchanged"
+    verdict: &'static str,
+}
+
+/// The whole comparison, as printed with `--format json`.
+#[derive(Serialize)]
+struct Comparison<'a> {
+    /// First recording
+    before: &'a str,
+    /// Second recording
+    after: &'a str,
+    /// Significance level used
+    alpha: f64,
+    /// Minimum |Cohen's d| used
+    min_effect: f64,
+    /// Compared metrics, significant changes first
+    metrics: Vec<MetricChange>,
+    /// Metrics with too little data in either recording
+    insufficient_data: Vec<&'static str>,
+}
+
+/// Compare two recordings for `slow-rs compare`.
+pub fn run(args: &CompareArgs) -> std::io::Result<()> {
+    let before = replay::load(&args.before)?;
+    let after = replay::load(&args.after)?;
+
+    let mut metrics = Vec::new();
+    let mut insufficient_data = Vec::new();
+    for &(metric, unit, better, value_fn) in COMPARED {
+        let values = |samples: &[Metrics]| samples.iter().filter_map(value_fn).collect();
+        let a = Distribution::from_values(values(&before));
+        let b = Distribution::from_values(values(&after));
+        match (a, b) {
+            (Some(a), Some(b)) => {
+                metrics.push(compare(metric, unit, better, a, b, args));
+            }
+            // Metrics neither recording has are not worth mentioning
+            (None, None) => {}
+            _ => insufficient_data.push(metric),
+        }
+    }
+
+    // Significant changes first, largest effect first
+    metrics.sort_by(|x, y| {
+        let effect = |c: &MetricChange| c.cohens_d.map_or(f64::INFINITY, f64::abs);
+        (x.verdict == "unchanged")
+            .cmp(&(y.verdict == "unchanged"))
+            .then(effect(y).total_cmp(&effect(x)))
+    });
+    if !args.all {
+        metrics.retain(|c| c.verdict != "unchanged");
+    }
+
+    let comparison = Comparison {
+        before: &args.before,
+        after: &args.after,
+        alpha: args.alpha,
+        min_effect: args.min_effect,
+        metrics,
+        insufficient_data,
+    };
+
+    match args.format {
+        CompareFormat::Json => {
+            let json = serde_json::to_string_pretty(&comparison)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+            println!("{}", json);
+        }
+        CompareFormat::Table => print_table(&comparison, before.len(), after.len()),
+    }
+    Ok(())
+}
+
+/// Compare one metric's distributions.
+fn compare(
+    metric: &'static str,
+    unit: &'static str,
+    better: Better,
+    before: Distribution,
+    after: Distribution,
+    args: &CompareArgs,
+) -> MetricChange {
+    let diff = after.mean - before.mean;
+    let pooled_sd = (((before.count - 1) as f64 * before.std_dev.powi(2)
+        + (after.count - 1) as f64 * after.std_dev.powi(2))
+        / (before.count + after.count - 2) as f64)
+        .sqrt();
+    let cohens_d = if pooled_sd > 0.0 {
+        Some(diff / pooled_sd)
+    } else {
+        // Both constant: no effect if equal, otherwise unbounded
+        (diff == 0.0).then_some(0.0)
+    };
+    let p_value = welch_p_value(&before, &after);
+
+    let large_enough = cohens_d.map_or(true, |d| d.abs() >= args.min_effect);
+    let verdict = if p_value < args.alpha && large_enough {
+        match (better, diff > 0.0) {
+            (Better::Higher, true) | (Better::Lower, false) => "better",
+            _ => "worse",
+        }
+    } else {
+        "unchanged"
+    };
+
+    MetricChange {
+        metric,
+        unit,
+        change_percent: (before.mean != 0.0).then(|| diff / before.mean.abs() * 100.0),
+        before,
+        after,
+        cohens_d,
+        p_value,
+        verdict,
+    }
+}
+
+/// Two-sided p-value of Welch's unequal-variances t-test.
+fn welch_p_value(a: &Distribution, b: &Distribution) -> f64 {
+    let (va, vb) = (a.mean_variance(), b.mean_variance());
+    let se = (va + vb).sqrt();
+    if se == 0.0 {
+        return if a.mean == b.mean { 1.0 } else { 0.0 };
+    }
+    let t = (b.mean - a.mean) / se;
+    let df =
+        (va + vb).powi(2) / (va.powi(2) / (a.count - 1) as f64 + vb.powi(2) / (b.count - 1) as f64);
+    // P(|T| > |t|) for Student's t with df degrees of freedom
+    incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// Regularized incomplete beta function I_x(a, b).
+///
+/// Continued fraction evaluation (Lentz's method), as in Numerical Recipes.
+fn incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    // The continued fraction converges quickly only below this point
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction for [`incomplete_beta`].
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const TINY: f64 = 1e-300;
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut h = d;
+
+    for m in 1..300 {
+        let m = m as f64;
+        for numerator in [
+            m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)),
+            -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)),
+        ] {
+            d = 1.0 + numerator * d;
+            if d.abs() < TINY {
+                d = TINY;
+            }
+            c = 1.0 + numerator / c;
+            if c.abs() < TINY {
+                c = TINY;
+            }
+            d = 1.0 / d;
+            h *= d * c;
+        }
+        if (d * c - 1.0).abs() < 1e-12 {
+            break;
+        }
+    }
+    h
+}
+
+/// Natural log of the gamma function (Lanczos approximation, x > 0).
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 6] = [
+        76.180_091_729_471_46,
+        -86.505_320_329_416_77,
+        24.014_098_240_830_91,
+        -1.231_739_572_450_155,
+        0.001_208_650_973_866_179,
+        -0.000_005_395_239_384_953,
+    ];
+    let tmp = x + 5.5;
+    let tmp = tmp - (x + 0.5) * tmp.ln();
+    let mut series = 1.000_000_000_190_015;
+    for (i, coefficient) in COEFFICIENTS.iter().enumerate() {
+        series += coefficient / (x + 1.0 + i as f64);
+    }
+    -tmp + (2.506_628_274_631_000_5 * series / x).ln()
+}
+
+/// Print the comparison as an aligned table.
+fn print_table(comparison: &Comparison, before_samples: usize, after_samples: usize) {
+    println!(
+        "Before: {} ({} samples)\nAfter:  {} ({} samples)\n",
+        comparison.before, before_samples, comparison.after, after_samples
+    );
+
+    if comparison.metrics.is_empty() {
+        println!(
+            "No significant changes (p < {}, |d| >= {}).",
+            comparison.alpha, comparison.min_effect
+        );
+    } else {
+        println!(
+            "{:<20} {:>6} {:>11} {:>11} {:>8} {:>7} {:>9}  verdict",
+            "metric", "unit", "before", "after", "change", "d", "p"
+        );
+        for change in &comparison.metrics {
+            println!(
+                "{:<20} {:>6} {:>11} {:>11} {:>8} {:>7} {:>9}  {}",
+                change.metric,
+                change.unit,
+                format_value(change.before.mean),
+                format_value(change.after.mean),
+                change
+                    .change_percent
+                    .map(|pct| format!("{:+.1}%", pct))
+                    .unwrap_or_else(|| "-".to_string()),
+                change
+                    .cohens_d
+                    .map(|d| format!("{:+.2}", d))
+                    .unwrap_or_else(|| "inf".to_string()),
+                format_p(change.p_value),
+                change.verdict
+            );
+        }
+    }
+
+    if !comparison.insufficient_data.is_empty() {
+        println!(
+            "\nOnly in one recording (or fewer than 2 samples): {}",
+            comparison.insufficient_data.join(", ")
+        );
+    }
+}
+
+/// Format a p-value (tiny values in scientific notation).
+fn format_p(p: f64) -> String {
+    if p < 0.001 {
+        format!("{:.1e}", p)
+    } else {
+        format!("{:.3}", p)
+    }
+}
@@ -4,7 +4,10 @@
 //! The configuration controls measurement intervals, output files,
 //! benchmark parameters, and display mode.
 
-use clap::Parser;
+use std::path::Path;
+use std::str::FromStr;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
 /// System slowness diagnostic monitor.
 ///
@@ -55,7 +58,8 @@ pub struct Config {
     ///
     /// This file is used to measure disk read/write speeds. It will be
     /// created automatically if it doesn't exist. Use a path on the
-    /// disk you want to test.
+    /// disk you want to test. The file itself is only read; write
+    /// benchmarks use scratch files next to it.
     #[arg(short, long, default_value = "/tmp/slowtest.bin")]
     pub test_file: String,
 
@@ -93,4 +97,503 @@ pub struct Config {
     /// Enable this when you specifically want to measure disk performance.
     #[arg(long)]
     pub io_bench: bool,
+
+    /// Enable the STREAM-style memory bandwidth benchmark.
+    ///
+    /// Runs copy/scale/add/triad kernels over three large arrays and
+    /// reports GB/s. Degraded DIMMs, single-channel operation or thermal
+    /// memory throttling show up as a drop in these numbers.
+    #[arg(long)]
+    pub mem_bench: bool,
+
+    /// Size of each memory bandwidth array in MB.
+    ///
+    /// Should be well above the last-level cache size (three arrays are
+    /// allocated, so the default uses 192MB in total).
+    #[arg(long, default_value_t = 64)]
+    pub mem_bench_size_mb: usize,
+
+    /// Number of threads for the memory bandwidth benchmark.
+    ///
+    /// A single thread rarely saturates all memory channels; use the
+    /// number of physical cores to measure total platform bandwidth.
+    #[arg(long, default_value_t = 1)]
+    pub mem_bench_threads: usize,
+
+    /// Enable the pointer-chasing memory latency benchmark.
+    ///
+    /// Walks randomised linked lists sized for L1, L2, the last-level
+    /// cache and DRAM, reporting nanoseconds per access at each level.
+    #[arg(long)]
+    pub latency_bench: bool,
+
+    /// Enable the multi-core compute scaling benchmark.
+    ///
+    /// Runs the compute kernel pinned to each core in turn, then on all
+    /// cores at once, to find throttled or stolen cores and measure
+    /// scaling efficiency.
+    #[arg(long)]
+    pub scaling_bench: bool,
+
+    /// Enable the random 4K IOPS and latency benchmark.
+    ///
+    /// Issues random 4K reads and writes against the test file using
+    /// O_DIRECT (bypassing the page cache, so root is not required) and
+    /// reports IOPS and p50/p99/p99.9 latency. Shares the test file with
+    /// `--io-bench`.
+    #[arg(long)]
+    pub iops_bench: bool,
+
+    /// Number of concurrent outstanding requests for the IOPS benchmark.
+    ///
+    /// 1 measures pure per-request latency; 32 approximates a busy
+    /// desktop or a database under load.
+    #[arg(long, default_value_t = 1)]
+    pub iops_queue_depth: usize,
+
+    /// Duration of each IOPS benchmark phase (read, write) in milliseconds.
+    #[arg(long, default_value_t = 1000)]
+    pub iops_duration_ms: u64,
+
+    /// Enable the fsync latency benchmark.
+    ///
+    /// Performs small writes each followed by fsync (then fdatasync) in
+    /// a scratch file next to the test file, and records the latency
+    /// distribution. Catches sync stalls from writeback congestion or a
+    /// struggling SSD controller.
+    #[arg(long)]
+    pub fsync_bench: bool,
+
+    /// Number of write+sync operations per fsync benchmark mode.
+    #[arg(long, default_value_t = 32)]
+    pub fsync_count: usize,
+
+    /// Enable the filesystem metadata benchmark.
+    ///
+    /// Creates, stats, renames and unlinks many small files in a
+    /// temporary directory and reports ops/s for each. Slow `ls`,
+    /// `git status` and builds are usually metadata-bound.
+    #[arg(long)]
+    pub metadata_bench: bool,
+
+    /// Directory in which the metadata benchmark creates its scratch directory.
+    ///
+    /// Point this at the filesystem you want to test (e.g. an NFS mount).
+    #[arg(long, default_value = "/tmp")]
+    pub metadata_dir: String,
+
+    /// Number of files per metadata benchmark run.
+    #[arg(long, default_value_t = 1000)]
+    pub metadata_files: usize,
+
+    /// Enable the scheduler wakeup latency benchmark (cyclictest-like).
+    ///
+    /// A thread repeatedly sleeps for a fixed period using a
+    /// high-resolution timer and records how late it wakes up.
+    #[arg(long)]
+    pub wakeup_bench: bool,
+
+    /// Timer period for the wakeup latency benchmark in microseconds.
+    #[arg(long, default_value_t = 1000)]
+    pub wakeup_period_us: u64,
+
+    /// Number of wakeups measured per wakeup latency benchmark run.
+    #[arg(long, default_value_t = 200)]
+    pub wakeup_samples: usize,
+
+    /// Record a benchmark baseline from this run.
+    ///
+    /// Run this on a known-good system. The median of each benchmark over
+    /// the run is saved (after every sample) to the baseline file, keyed
+    /// by hostname and hardware fingerprint.
+    #[arg(long)]
+    pub record_baseline: bool,
+
+    /// Path to the baseline state file.
+    ///
+    /// Defaults to `$XDG_STATE_HOME/slow-rs/baselines.json`
+    /// (`~/.local/state/slow-rs/baselines.json`).
+    #[arg(long)]
+    pub baseline_file: Option<String>,
+
+    /// Slowdown factor versus baseline that counts as a regression.
+    ///
+    /// 1.5 flags benchmarks running at less than 67% of their baseline
+    /// performance; twice that slowdown is flagged as critical. Must be
+    /// greater than 1.
+    #[arg(long, default_value_t = 1.5, value_parser = parse_regression_factor)]
+    pub regression_factor: f64,
+
+    /// Additional disk/filesystem to benchmark: `PATH[:SIZE_MB[:BENCHES]]`.
+    ///
+    /// May be repeated to compare several mounts side by side, e.g.
+    /// `--io-target /home --io-target /mnt/scratch:64:iops,fsync,meta`.
+    /// PATH is a directory (the test file is created inside it) or a test
+    /// file path. SIZE_MB defaults to `--file-size-mb`. BENCHES is a
+    /// comma-separated subset of `io`, `iops`, `fsync` and `meta`
+    /// (default `io`); `--iops-*`, `--fsync-count` and `--metadata-files`
+    /// apply to all targets. SIZE_MB and BENCHES are taken from the right,
+    /// so a PATH containing ':' works as is if it exists, or in the full
+    /// form (`/mnt/a:b::iops`).
+    #[arg(long = "io-target", value_name = "PATH[:SIZE_MB[:BENCHES]]")]
+    pub io_targets: Vec<IoTarget>,
+
+    /// Back off heavy benchmarks while the system is already stressed.
+    ///
+    /// When I/O or memory pressure (PSI) or iowait reach the warning
+    /// threshold, the I/O, memory bandwidth and `--io-target` workloads
+    /// are shrunk to a quarter; at the critical threshold they (and the
+    /// scaling/latency benchmarks) are skipped. Skips and their reason are
+    /// logged in the `bench_backoff*` and `benchmarks_skipped` columns.
+    #[arg(long)]
+    pub adaptive: bool,
+
+    /// Seconds between compute benchmark runs (default: every sample).
+    ///
+    /// Covers the allocation, compute, scaling, memory bandwidth/latency
+    /// and wakeup benchmarks. Passive /proc sampling still happens every
+    /// `--interval`; samples without a run leave these columns empty.
+    #[arg(long, value_name = "SECS")]
+    pub compute_interval: Option<u64>,
+
+    /// Seconds between I/O benchmark runs (default: every sample).
+    ///
+    /// Covers the throughput, IOPS, fsync, metadata and `--io-target`
+    /// benchmarks, e.g. `--interval 1 --compute-interval 30 --io-interval 300`.
+    #[arg(long, value_name = "SECS")]
+    pub io_interval: Option<u64>,
+
+    /// Serve Prometheus metrics on this address (e.g. `0.0.0.0:9465`).
+    ///
+    /// The latest sample is exposed on `/metrics` in the Prometheus text
+    /// format, together with per-device disk and network counters.
+    #[arg(long, value_name = "ADDR")]
+    pub prometheus_listen: Option<String>,
+
+    /// Write Prometheus metrics to `slow_rs.prom` in this directory.
+    ///
+    /// For node_exporter's textfile collector
+    /// (`--collector.textfile.directory`). The file is replaced atomically
+    /// after every sample and uses the same names as `--prometheus-listen`.
+    #[arg(long, value_name = "DIR")]
+    pub textfile_dir: Option<String>,
+
+    /// Append one JSON object per sample to this file (`-` for stdout).
+    ///
+    /// Unlike the CSV, records are fully nested: IPMI sensor lists and
+    /// per-device disk/network deltas are included as arrays and objects.
+    #[arg(long, value_name = "PATH")]
+    pub jsonl_file: Option<String>,
+
+    /// Write InfluxDB line protocol to a file, `-` (stdout) or `udp://HOST:PORT`.
+    ///
+    /// One measurement per subsystem (cpu, mem, disk, net, psi, temps,
+    /// power, smart, ipmi, bench), tagged with host and device.
+    #[arg(long, value_name = "DEST")]
+    pub influx_output: Option<String>,
+
+    /// Store samples in a SQLite database (see `slow-rs query`).
+    ///
+    /// Samples, per-device disk/network rows, temperatures,
+    /// recommendations and recommendation start/clear events are kept in
+    /// separate tables.
+    #[arg(long, value_name = "PATH")]
+    pub sqlite_file: Option<String>,
+
+    /// Delete SQLite rows older than this many days (0 = keep forever).
+    #[arg(long, default_value_t = 30, value_name = "DAYS")]
+    pub sqlite_retention_days: u64,
+
+    /// Rotate the CSV log once it reaches this size (MB).
+    #[arg(long, value_name = "MB")]
+    pub rotate_size_mb: Option<u64>,
+
+    /// Rotate the CSV log at local midnight.
+    #[arg(long)]
+    pub rotate_daily: bool,
+
+    /// Number of rotated CSV logs to keep (0 = keep all).
+    #[arg(long, default_value_t = 7, value_name = "N")]
+    pub rotate_keep: usize,
+
+    /// Compression for rotated CSV logs.
+    #[arg(long, value_enum, default_value_t = Compression::Gzip)]
+    pub rotate_compress: Compression,
+
+    /// Save incidents to this JSON file and load earlier ones from it.
+    ///
+    /// An incident opens when a recommendation first fires and closes after
+    /// it has stayed clear for `--incident-recovery` seconds. Without this,
+    /// incidents are only kept in memory (and in SQLite with `--sqlite-file`).
+    #[arg(long)]
+    pub incident_file: Option<String>,
+
+    /// Seconds a recommendation must stay clear before its incident closes.
+    #[arg(long, default_value_t = 60, value_name = "SECS")]
+    pub incident_recovery: u64,
+
+    /// Subcommand (default: monitor)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Compression for rotated log files.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Keep rotated files uncompressed
+    None,
+    /// gzip (`.gz`)
+    Gzip,
+    /// Zstandard (`.zst`)
+    Zstd,
+}
+
+/// Subcommands besides monitoring.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Print summaries from a `--sqlite-file` database
+    Query(QueryArgs),
+    /// Play back a recorded CSV or JSON Lines file in the TUI
+    Replay(ReplayArgs),
+    /// Summarise a recording as a Markdown or HTML report
+    Report(ReportArgs),
+    /// Compare two recordings (e.g. before and after a change)
+    Compare(CompareArgs),
+}
+
+/// Arguments for `slow-rs query`.
+#[derive(Args, Debug, Clone)]
+pub struct QueryArgs {
+    /// Summary to print
+    #[arg(value_enum)]
+    pub summary: QuerySummary,
+
+    /// SQLite database written with `--sqlite-file`
+    #[arg(long, default_value = "metrics.db")]
+    pub db: String,
+
+    /// Only include the last N hours (default: everything)
+    #[arg(long, value_name = "HOURS")]
+    pub since_hours: Option<u64>,
+
+    /// Maximum number of rows to print
+    #[arg(long, default_value_t = 10)]
+    pub limit: usize,
+
+    /// Interval length for `worst` (minutes)
+    #[arg(long, default_value_t = 5, value_name = "MINUTES")]
+    pub bucket_minutes: u64,
+}
+
+/// Arguments for `slow-rs replay`.
+#[derive(Args, Debug, Clone)]
+pub struct ReplayArgs {
+    /// Recorded samples (`--csv-file` or `--jsonl-file` output, may be .gz/.zst)
+    pub file: String,
+
+    /// Initial playback speed (1 = real time)
+    #[arg(long, default_value_t = 1.0)]
+    pub speed: f64,
+}
+
+/// Arguments for `slow-rs report`.
+#[derive(Args, Debug, Clone)]
+pub struct ReportArgs {
+    /// Recorded samples (anything `slow-rs replay` can load)
+    pub file: String,
+
+    /// Output file (default: stdout; Markdown charts need a file)
+    #[arg(short, long)]
+    pub output: Option<String>,
+
+    /// Report format (default: from the output extension, else Markdown)
+    #[arg(long, value_enum)]
+    pub format: Option<ReportFormat>,
+
+    /// Number of recommendations to list
+    #[arg(long, default_value_t = 10)]
+    pub top: usize,
+}
+
+/// Output format for `slow-rs report`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Markdown, with charts as SVG files next to the report
+    Markdown,
+    /// Self-contained HTML with inline SVG charts
+    Html,
+}
+
+/// Arguments for `slow-rs compare`.
+#[derive(Args, Debug, Clone)]
+pub struct CompareArgs {
+    /// Recording before the change
+    pub before: String,
+
+    /// Recording after the change
+    pub after: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = CompareFormat::Table)]
+    pub format: CompareFormat,
+
+    /// Significance level for Welch's t-test
+    #[arg(long, default_value_t = 0.05)]
+    pub alpha: f64,
+
+    /// Smallest |Cohen's d| that counts as a change
+    #[arg(long, default_value_t = 0.5)]
+    pub min_effect: f64,
+
+    /// Also list metrics that didn't change significantly
+    #[arg(long)]
+    pub all: bool,
+}
+
+/// Output format for `slow-rs compare`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareFormat {
+    /// Aligned text table
+    Table,
+    /// JSON document
+    Json,
+}
+
+/// Canned summaries for `slow-rs query`.
+#[derive(ValueEnum, Debug, Clone, Copy)]
+pub enum QuerySummary {
+    /// Intervals with the highest pressure stalls
+    Worst,
+    /// Averages per hour (UTC)
+    Hourly,
+    /// Recommendations with when they started and cleared
+    Incidents,
+}
+
+impl Config {
+    /// Whether an output sink writes samples to stdout.
+    ///
+    /// The TUI and headless status lines must stay off stdout in that case.
+    pub fn sink_uses_stdout(&self) -> bool {
+        self.jsonl_file.as_deref() == Some("-") || self.influx_output.as_deref() == Some("-")
+    }
+}
+
+/// Parse `--regression-factor`, which must be a slowdown (greater than 1).
+fn parse_regression_factor(s: &str) -> Result<f64, String> {
+    let factor: f64 = s
+        .parse()
+        .map_err(|_| format!("invalid factor '{}' (expected a number)", s))?;
+    if factor.is_finite() && factor > 1.0 {
+        Ok(factor)
+    } else {
+        Err(format!("factor must be greater than 1 (got {})", s))
+    }
+}
+
+/// A disk or filesystem benchmarked via `--io-target`.
+#[derive(Debug, Clone)]
+pub struct IoTarget {
+    /// Path as given on the command line (used as the label in metrics)
+    pub path: String,
+    /// Test file size in MB (None = use `--file-size-mb`)
+    pub size_mb: Option<usize>,
+    /// Run the sequential throughput benchmark
+    pub io: bool,
+    /// Run the random 4K IOPS benchmark
+    pub iops: bool,
+    /// Run the fsync/fdatasync latency benchmark
+    pub fsync: bool,
+    /// Run the filesystem metadata benchmark
+    pub meta: bool,
+}
+
+impl IoTarget {
+    /// Test file used by this target's throughput, IOPS and fsync benchmarks.
+    pub fn test_file(&self) -> String {
+        if Path::new(&self.path).is_dir() {
+            Path::new(&self.path)
+                .join("slowtest.bin")
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            self.path.clone()
+        }
+    }
+
+    /// Directory used by this target's metadata benchmark.
+    pub fn dir(&self) -> String {
+        let path = Path::new(&self.path);
+        if path.is_dir() {
+            self.path.clone()
+        } else {
+            path.parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_else(|| ".".into())
+        }
+    }
+
+    /// Whether this target needs a test file.
+    pub fn needs_test_file(&self) -> bool {
+        self.io || self.iops
+    }
+}
+
+impl FromStr for IoTarget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // SIZE and BENCHES are split off from the right so that PATH may
+        // contain ':'. An existing path is taken as a whole.
+        let mut parts: Vec<&str> = if Path::new(s).exists() {
+            vec![s]
+        } else {
+            s.rsplitn(3, ':').collect()
+        };
+        parts.reverse();
+        let mut parts = parts.into_iter();
+        let path = parts.next().unwrap_or_default();
+        if path.is_empty() {
+            return Err("missing path".into());
+        }
+
+        let size_mb = match parts.next() {
+            None | Some("") => None,
+            Some(size) => Some(
+                size.parse()
+                    .map_err(|_| format!("invalid size '{}' (expected MB)", size))?,
+            ),
+        };
+
+        let mut target = IoTarget {
+            path: path.to_string(),
+            size_mb,
+            io: false,
+            iops: false,
+            fsync: false,
+            meta: false,
+        };
+        match parts.next() {
+            None | Some("") => target.io = true,
+            Some(benches) => {
+                for bench in benches.split(',') {
+                    match bench {
+                        "io" => target.io = true,
+                        "iops" => target.iops = true,
+                        "fsync" => target.fsync = true,
+                        "meta" => target.meta = true,
+                        other => {
+                            return Err(format!(
+                                "unknown benchmark '{}' (expected io, iops, fsync or meta)",
+                                other
+                            ))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(target)
+    }
 }
@@ -0,0 +1,351 @@
+//! CSV metrics log for slow-rs.
+//!
+//! The CSV columns are the [`Metrics`] field names, so adding, removing or
+//! reordering a field changes the layout. Appending rows under an older
+//! header would silently misalign every column, so before appending, the
+//! existing header is compared against the current one. On a mismatch the
+//! old file is rotated aside (e.g. `metrics.schema1.20250101-120000.csv`)
+//! and a fresh file with the current header is started.
+//!
+//! Every row starts with a `schema_version` column
+//! ([`crate::metrics::SCHEMA_VERSION`]), so analysis tools can tell which
+//! layout a file uses.
+//!
+//! # Rotation
+//!
+//! For long unattended runs the log can also be rotated by size and/or
+//! daily (`--rotate-size-mb`, `--rotate-daily`). The current file is renamed
+//! to e.g. `metrics.20250101-120000.csv`, a new file with a fresh header is
+//! started, and the rotated file is compressed and old ones pruned in the
+//! background. A second rotation within the same second gets a counter
+//! (`metrics.20250101-120000-1.csv`) rather than replacing the first.
+//! Compression writes to a temp file and only replaces the rotated file
+//! once complete, so running out of disk space leaves the uncompressed
+//! file in place instead of losing data.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
+
+use chrono::{Local, NaiveDate};
+
+use crate::config::{Compression, Config};
+use crate::metrics::Metrics;
+
+/// Timestamp format used in rotated file names.
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Length of a formatted [`STAMP_FORMAT`] timestamp.
+const STAMP_LEN: usize = 15;
+
+/// When and how the log is rotated.
+#[derive(Clone, Copy, Debug)]
+pub struct Rotation {
+    /// Rotate once the file reaches this many bytes
+    pub max_bytes: Option<u64>,
+    /// Rotate when the local date changes
+    pub daily: bool,
+    /// Number of rotated files to keep (0 = keep all)
+    pub keep: usize,
+    /// Compression applied to rotated files
+    pub compression: Compression,
+}
+
+impl Rotation {
+    /// Rotation settings from the CLI (None if rotation is disabled).
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.rotate_size_mb.is_some() || config.rotate_daily).then(|| Self {
+            max_bytes: config.rotate_size_mb.map(|mb| mb * 1024 * 1024),
+            daily: config.rotate_daily,
+            keep: config.rotate_keep,
+            compression: config.rotate_compress,
+        })
+    }
+}
+
+/// Append-only CSV log with schema checking and optional rotation.
+pub struct CsvLog {
+    /// Path of the active log file
+    path: PathBuf,
+    /// CSV writer (header already written or present in the file)
+    writer: csv::Writer<File>,
+    /// Rotation settings (None = grow forever)
+    rotation: Option<Rotation>,
+    /// Local date the active file was started (for daily rotation)
+    started: NaiveDate,
+    /// Background compression/pruning of the last rotated file
+    housekeeping: Option<JoinHandle<()>>,
+}
+
+impl CsvLog {
+    /// Open the log for appending.
+    ///
+    /// If the file exists with a different header, it is renamed first and
+    /// the new path is returned alongside the log.
+    pub fn open(
+        path: &str,
+        rotation: Option<Rotation>,
+    ) -> std::io::Result<(Self, Option<PathBuf>)> {
+        let path = PathBuf::from(path);
+        let expected = header()?;
+
+        let mut rotated = None;
+        if let Some((existing, first_row)) = read_head(&path)? {
+            if existing != expected {
+                let version = existing_version(&existing, first_row.as_deref());
+                let target = stamped_path(&path, Some(&format!("schema{}", version)));
+                std::fs::rename(&path, &target)?;
+                rotated = Some(target);
+            }
+        }
+
+        // An existing file counts as started on the day it was last written
+        let started = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .map(|t| chrono::DateTime::<Local>::from(t).date_naive())
+            .unwrap_or_else(|_| Local::now().date_naive());
+
+        let log = Self {
+            writer: open_writer(&path)?,
+            path,
+            rotation,
+            started,
+            housekeeping: None,
+        };
+        Ok((log, rotated))
+    }
+
+    /// Append a sample, rotating before (daily) or after (size) as configured.
+    pub fn write(&mut self, metrics: &Metrics) -> std::io::Result<()> {
+        if let Some(rotation) = self.rotation {
+            if rotation.daily && Local::now().date_naive() != self.started {
+                self.rotate(rotation)?;
+            }
+        }
+
+        self.writer
+            .serialize(metrics)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.writer.flush()?;
+
+        if let Some(rotation) = self.rotation {
+            if let Some(max_bytes) = rotation.max_bytes {
+                if std::fs::metadata(&self.path)?.len() >= max_bytes {
+                    self.rotate(rotation)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Move the active file aside and start a new one with a fresh header.
+    fn rotate(&mut self, rotation: Rotation) -> std::io::Result<()> {
+        self.writer.flush()?;
+        let rotated = stamped_path(&self.path, None);
+        std::fs::rename(&self.path, &rotated)?;
+        self.writer = open_writer(&self.path)?;
+        self.started = Local::now().date_naive();
+
+        // Only one housekeeping job at a time
+        if let Some(previous) = self.housekeeping.take() {
+            let _ = previous.join();
+        }
+        let path = self.path.clone();
+        self.housekeeping = Some(std::thread::spawn(move || {
+            if rotation.compression != Compression::None {
+                // On failure (e.g. disk full) the uncompressed file is kept
+                let _ = compress(&rotated, rotation.compression);
+            }
+            if rotation.keep > 0 {
+                let _ = prune(&path, rotation.keep);
+            }
+        }));
+        Ok(())
+    }
+}
+
+impl Drop for CsvLog {
+    fn drop(&mut self) {
+        // Let a running compression finish rather than leave a stray temp file
+        if let Some(housekeeping) = self.housekeeping.take() {
+            let _ = housekeeping.join();
+        }
+    }
+}
+
+/// Open a log file for appending, writing the header if it is empty.
+fn open_writer(path: &Path) -> std::io::Result<csv::Writer<File>> {
+    let needs_header = read_head(path)?.is_none();
+    let file = OpenOptions::new().append(true).create(true).open(path)?;
+    Ok(csv::WriterBuilder::new()
+        .has_headers(needs_header)
+        .from_writer(file))
+}
+
+/// The header line for the current [`Metrics`] layout.
+pub fn header() -> std::io::Result<String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .serialize(Metrics::default())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))?;
+    let text = String::from_utf8_lossy(&bytes);
+    Ok(text.lines().next().unwrap_or_default().to_string())
+}
+
+/// Read the header and first data row of an existing, non-empty file.
+fn read_head(path: &Path) -> std::io::Result<Option<(String, Option<String>)>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut lines = BufReader::new(file).lines();
+    let Some(header) = lines.next().transpose()? else {
+        return Ok(None);
+    };
+    if header.trim().is_empty() {
+        return Ok(None);
+    }
+    let first_row = lines.next().transpose()?;
+    Ok(Some((header.trim_end().to_string(), first_row)))
+}
+
+/// Schema version of an existing file (files without the column are version 1).
+fn existing_version(header: &str, first_row: Option<&str>) -> u32 {
+    if !header.starts_with("schema_version,") {
+        return 1;
+    }
+    first_row
+        .and_then(|row| row.split(',').next())
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1)
+}
+
+/// File stem and extension of the log (e.g. "metrics", "csv").
+fn stem_and_extension(path: &Path) -> (String, Option<String>) {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "metrics".to_string());
+    let ext = path.extension().map(|e| e.to_string_lossy().into_owned());
+    (stem, ext)
+}
+
+/// Path a log is moved to, e.g. `metrics.20250101-120000.csv` or, with a
+/// tag, `metrics.schema1.20250101-120000.csv`.
+///
+/// If that name (or its compressed archive) is already taken by an earlier
+/// rotation in the same second, a counter is appended to the timestamp
+/// (`metrics.20250101-120000-1.csv`).
+fn stamped_path(path: &Path, tag: Option<&str>) -> PathBuf {
+    let (stem, ext) = stem_and_extension(path);
+    let mut base = stem;
+    if let Some(tag) = tag {
+        base.push('.');
+        base.push_str(tag);
+    }
+    base.push('.');
+    base.push_str(&Local::now().format(STAMP_FORMAT).to_string());
+    let ext = ext.map(|e| format!(".{}", e)).unwrap_or_default();
+
+    let taken = |candidate: &Path| {
+        ["", ".gz", ".zst"]
+            .iter()
+            .any(|suffix| PathBuf::from(format!("{}{}", candidate.display(), suffix)).exists())
+    };
+    let mut candidate = path.with_file_name(format!("{}{}", base, ext));
+    let mut counter = 1;
+    while taken(&candidate) {
+        candidate = path.with_file_name(format!("{}-{}{}", base, counter, ext));
+        counter += 1;
+    }
+    candidate
+}
+
+/// Compress a rotated file, replacing it only once the archive is complete.
+fn compress(path: &Path, compression: Compression) -> std::io::Result<()> {
+    let suffix = match compression {
+        Compression::None => return Ok(()),
+        Compression::Gzip => "gz",
+        Compression::Zstd => "zst",
+    };
+    let archive = PathBuf::from(format!("{}.{}", path.display(), suffix));
+    let tmp = PathBuf::from(format!("{}.tmp", archive.display()));
+
+    let result = (|| {
+        let mut input = File::open(path)?;
+        let output = File::create(&tmp)?;
+        let output = match compression {
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(output, flate2::Compression::default());
+                std::io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?
+            }
+            _ => {
+                let mut encoder = zstd::Encoder::new(output, 0)?;
+                std::io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?
+            }
+        };
+        output.sync_all()?;
+        std::fs::rename(&tmp, &archive)?;
+        std::fs::remove_file(path)
+    })();
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+/// Delete all but the newest `keep` rotated files of a log.
+///
+/// Only matches names written by size/daily rotation
+/// (`<stem>.<stamp>[-<counter>].<ext>[.gz|.zst]`), not schema rotations.
+fn prune(path: &Path, keep: usize) -> std::io::Result<()> {
+    let (stem, ext) = stem_and_extension(path);
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let prefix = format!("{}.", stem);
+    let ext_suffix = ext.map(|e| format!(".{}", e)).unwrap_or_default();
+
+    let mut rotated: Vec<((String, u64), PathBuf)> = std::fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let rest = name.strip_prefix(&prefix)?;
+            let stamp = rest.get(..STAMP_LEN)?;
+            let is_stamp =
+                stamp
+                    .chars()
+                    .enumerate()
+                    .all(|(i, c)| if i == 8 { c == '-' } else { c.is_ascii_digit() });
+            let mut tail = &rest[STAMP_LEN..];
+            let mut counter = 0;
+            if let Some(after_dash) = tail.strip_prefix('-') {
+                let digits = after_dash
+                    .find(|c: char| !c.is_ascii_digit())
+                    .unwrap_or(after_dash.len());
+                counter = after_dash[..digits].parse().ok()?;
+                tail = &after_dash[digits..];
+            }
+            let tail = tail.strip_prefix(ext_suffix.as_str())?;
+            let known_tail = matches!(tail, "" | ".gz" | ".zst");
+            (is_stamp && known_tail).then(|| ((stamp.to_string(), counter), entry.path()))
+        })
+        .collect();
+
+    // Newest first (stamps sort chronologically, then by counter)
+    rotated.sort_by(|a, b| b.0.cmp(&a.0));
+    for (_, old) in rotated.into_iter().skip(keep) {
+        std::fs::remove_file(old)?;
+    }
+    Ok(())
+}
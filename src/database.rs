@@ -0,0 +1,609 @@
+//! SQLite storage backend for slow-rs.
+//!
+//! A CSV that grows forever is painful to analyse. With `--sqlite-file`,
+//! samples are also stored in a SQLite database with time-based retention,
+//! and `slow-rs query` prints canned summaries over it.
+//!
+//! # Tables
+//!
+//! - `samples`: one row per sample with the most useful columns for
+//!   queries, plus the full sample as JSON in `data`
+//! - `disk_stats` / `net_stats`: per-device deltas for each sample
+//! - `temperatures`: one row per sensor reading
+//! - `recommendations`: recommendations active in each sample
+//! - `events`: when an incident ([`crate::incidents`]) started, escalated
+//!   ("changed") or cleared, keyed by its `problem`
+//!   ([`Recommendation::kind`]) so that a warning turning critical is one
+//!   "changed" event
+//!
+//! Child tables reference `samples(id)` with `ON DELETE CASCADE`, so
+//! retention only has to delete old samples and events.
+
+use std::collections::BTreeMap;
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::config::{QueryArgs, QuerySummary};
+use crate::incidents::{Incident as TrackedIncident, IncidentEvent};
+use crate::metrics::{format_duration, parse_labeled, Metrics};
+use crate::recommendations::Recommendation;
+use crate::thresholds::Severity;
+
+/// Schema version stored in the `meta` table.
+const SCHEMA_VERSION: i64 = 2;
+
+/// Table definitions (idempotent).
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS samples (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    datetime TEXT NOT NULL,
+    cpu_usage_percent REAL,
+    load_avg_1 REAL,
+    cpu_iowait INTEGER,
+    cpu_steal INTEGER,
+    mem_used_mb INTEGER,
+    mem_available_mb INTEGER,
+    swap_used_mb INTEGER,
+    cpu_pressure_some_avg10 REAL,
+    mem_pressure_some_avg10 REAL,
+    io_pressure_some_avg10 REAL,
+    cpu_temp_celsius REAL,
+    max_temp_celsius REAL,
+    io_read_mb_per_sec REAL,
+    io_write_mb_per_sec REAL,
+    iops_read_p99_us REAL,
+    fsync_p99_ms REAL,
+    compute_duration_ms REAL,
+    baseline_worst_pct REAL,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS samples_timestamp ON samples(timestamp);
+CREATE TABLE IF NOT EXISTS disk_stats (
+    sample_id INTEGER NOT NULL REFERENCES samples(id) ON DELETE CASCADE,
+    device TEXT NOT NULL,
+    reads_completed INTEGER NOT NULL,
+    sectors_read INTEGER NOT NULL,
+    read_time_ms INTEGER NOT NULL,
+    writes_completed INTEGER NOT NULL,
+    sectors_written INTEGER NOT NULL,
+    write_time_ms INTEGER NOT NULL,
+    io_in_progress INTEGER NOT NULL,
+    io_time_ms INTEGER NOT NULL,
+    PRIMARY KEY (sample_id, device)
+);
+CREATE TABLE IF NOT EXISTS net_stats (
+    sample_id INTEGER NOT NULL REFERENCES samples(id) ON DELETE CASCADE,
+    device TEXT NOT NULL,
+    rx_bytes INTEGER NOT NULL,
+    tx_bytes INTEGER NOT NULL,
+    rx_packets INTEGER NOT NULL,
+    tx_packets INTEGER NOT NULL,
+    rx_errors INTEGER NOT NULL,
+    tx_errors INTEGER NOT NULL,
+    PRIMARY KEY (sample_id, device)
+);
+CREATE TABLE IF NOT EXISTS temperatures (
+    sample_id INTEGER NOT NULL REFERENCES samples(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    sensor TEXT NOT NULL,
+    source TEXT,
+    celsius REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS temperatures_sample ON temperatures(sample_id);
+CREATE TABLE IF NOT EXISTS recommendations (
+    sample_id INTEGER NOT NULL REFERENCES samples(id) ON DELETE CASCADE,
+    severity TEXT NOT NULL,
+    title TEXT NOT NULL,
+    advice TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS recommendations_sample ON recommendations(sample_id);
+CREATE TABLE IF NOT EXISTS events (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    title TEXT NOT NULL,
+    severity TEXT NOT NULL,
+    problem TEXT
+);
+CREATE INDEX IF NOT EXISTS events_timestamp ON events(timestamp);
+";
+
+/// Convert a rusqlite error into an I/O error.
+fn sql_err(e: rusqlite::Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, e)
+}
+
+/// Open a database and create the schema if needed.
+fn open(path: &str) -> std::io::Result<Connection> {
+    let conn = Connection::open(path).map_err(sql_err)?;
+    conn.execute_batch("PRAGMA foreign_keys = ON; PRAGMA journal_mode = WAL;")
+        .map_err(sql_err)?;
+    conn.execute_batch(SCHEMA).map_err(sql_err)?;
+
+    let version: Option<String> = conn
+        .query_row(
+            "SELECT value FROM meta WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(sql_err)?;
+    match version {
+        None => {
+            conn.execute(
+                "INSERT INTO meta (key, value) VALUES ('schema_version', ?1)",
+                params![SCHEMA_VERSION.to_string()],
+            )
+            .map_err(sql_err)?;
+        }
+        // Version 1 keyed events by title only
+        Some(v) if v == "1" => {
+            conn.execute_batch(
+                "ALTER TABLE events ADD COLUMN problem TEXT;
+                 UPDATE meta SET value = '2' WHERE key = 'schema_version';",
+            )
+            .map_err(sql_err)?;
+        }
+        Some(v) if v != SCHEMA_VERSION.to_string() => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "{}: schema version {} is not supported (expected {})",
+                    path, v, SCHEMA_VERSION
+                ),
+            ));
+        }
+        Some(_) => {}
+    }
+
+    Ok(conn)
+}
+
+/// SQLite sink for samples, recommendations and events.
+pub struct Database {
+    /// Open connection
+    conn: Connection,
+    /// Rows older than this many seconds are deleted (0 = keep forever)
+    retention_secs: i64,
+}
+
+impl Database {
+    /// Open (or create) a database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or was written by an
+    /// incompatible schema version.
+    pub fn open(path: &str, retention_days: u64) -> std::io::Result<Self> {
+        Ok(Self {
+            conn: open(path)?,
+            retention_secs: retention_days as i64 * 86_400,
+        })
+    }
+
+    /// Store a sample with its recommendations and the incident changes it
+    /// caused ([`crate::incidents::IncidentTracker::changes`]), then apply
+    /// retention.
+    pub fn record(
+        &mut self,
+        m: &Metrics,
+        recommendations: &[Recommendation],
+        changes: &[(IncidentEvent, TrackedIncident)],
+    ) -> std::io::Result<()> {
+        let data = serde_json::to_string(m)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let tx = self.conn.transaction().map_err(sql_err)?;
+
+        tx.execute(
+            "INSERT INTO samples (timestamp, datetime, cpu_usage_percent, load_avg_1,
+                cpu_iowait, cpu_steal, mem_used_mb, mem_available_mb, swap_used_mb,
+                cpu_pressure_some_avg10, mem_pressure_some_avg10, io_pressure_some_avg10,
+                cpu_temp_celsius, max_temp_celsius, io_read_mb_per_sec, io_write_mb_per_sec,
+                iops_read_p99_us, fsync_p99_ms, compute_duration_ms, baseline_worst_pct, data)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16,
+                ?17, ?18, ?19, ?20, ?21)",
+            params![
+                m.timestamp,
+                m.datetime,
+                m.cpu_usage_percent,
+                m.load_avg_1,
+                m.cpu_iowait as i64,
+                m.cpu_steal as i64,
+                m.mem_used_mb as i64,
+                m.mem_available_mb as i64,
+                m.swap_used_mb as i64,
+                m.cpu_pressure_some_avg10,
+                m.mem_pressure_some_avg10,
+                m.io_pressure_some_avg10,
+                m.cpu_temp_celsius,
+                m.max_temp_celsius,
+                m.io_read_mb_per_sec,
+                m.io_write_mb_per_sec,
+                m.iops_read_p99_us,
+                m.fsync_p99_ms,
+                m.compute_duration_ms,
+                m.baseline_worst_pct,
+                data,
+            ],
+        )
+        .map_err(sql_err)?;
+        let sample_id = tx.last_insert_rowid();
+
+        for (device, d) in &m.disk_devices {
+            tx.execute(
+                "INSERT INTO disk_stats VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                params![
+                    sample_id,
+                    device,
+                    d.reads_completed as i64,
+                    d.sectors_read as i64,
+                    d.read_time_ms as i64,
+                    d.writes_completed as i64,
+                    d.sectors_written as i64,
+                    d.write_time_ms as i64,
+                    d.io_in_progress as i64,
+                    d.io_time_ms as i64,
+                ],
+            )
+            .map_err(sql_err)?;
+        }
+        for (device, n) in &m.net_interfaces {
+            tx.execute(
+                "INSERT INTO net_stats VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    sample_id,
+                    device,
+                    n.rx_bytes as i64,
+                    n.tx_bytes as i64,
+                    n.rx_packets as i64,
+                    n.tx_packets as i64,
+                    n.rx_errors as i64,
+                    n.tx_errors as i64,
+                ],
+            )
+            .map_err(sql_err)?;
+        }
+
+        for (kind, sensor, source, celsius) in temperature_rows(m) {
+            tx.execute(
+                "INSERT INTO temperatures VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![sample_id, kind, sensor, source, celsius],
+            )
+            .map_err(sql_err)?;
+        }
+
+        for rec in recommendations {
+            tx.execute(
+                "INSERT INTO recommendations VALUES (?1, ?2, ?3, ?4)",
+                params![sample_id, rec.severity.name(), rec.title, rec.advice],
+            )
+            .map_err(sql_err)?;
+        }
+
+        // Events: incidents that opened, escalated or closed. A closed
+        // incident is stamped with the first sample it was clear in.
+        for (event, incident) in changes {
+            let (kind, timestamp) = match event {
+                IncidentEvent::Opened => ("started", m.timestamp),
+                IncidentEvent::Escalated => ("changed", m.timestamp),
+                IncidentEvent::Closed => ("cleared", incident.closed.unwrap_or(m.timestamp)),
+            };
+            tx.execute(
+                "INSERT INTO events (timestamp, kind, title, severity, problem)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    timestamp,
+                    kind,
+                    incident.title,
+                    incident.peak_severity.name(),
+                    incident.kind
+                ],
+            )
+            .map_err(sql_err)?;
+        }
+
+        if self.retention_secs > 0 {
+            let cutoff = m.timestamp - self.retention_secs;
+            tx.execute("DELETE FROM samples WHERE timestamp < ?1", params![cutoff])
+                .map_err(sql_err)?;
+            tx.execute("DELETE FROM events WHERE timestamp < ?1", params![cutoff])
+                .map_err(sql_err)?;
+        }
+
+        tx.commit().map_err(sql_err)
+    }
+}
+
+/// Temperature readings as (kind, sensor, source, celsius) rows.
+fn temperature_rows(m: &Metrics) -> Vec<(&'static str, String, Option<String>, f64)> {
+    let mut rows = Vec::new();
+
+    if let Some(temp) = m.cpu_temp_celsius {
+        rows.push(("cpu", "cpu".to_string(), m.cpu_temp_source.clone(), temp));
+    }
+    for (sensor, temp) in m
+        .dimm_temps
+        .as_deref()
+        .map(parse_labeled)
+        .unwrap_or_default()
+    {
+        rows.push(("dimm", sensor.to_string(), m.dimm_temp_source.clone(), temp));
+    }
+    for (device, temp) in m
+        .disk_temps
+        .as_deref()
+        .map(parse_labeled)
+        .unwrap_or_default()
+    {
+        rows.push(("disk", device.to_string(), m.disk_temp_source.clone(), temp));
+    }
+    for t in &m.ipmi_temps {
+        rows.push((
+            "ipmi",
+            t.name.clone(),
+            Some("ipmi".to_string()),
+            t.temp_celsius,
+        ));
+    }
+
+    rows
+}
+
+/// Run `slow-rs query` and print the summary to stdout.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or queried.
+pub fn run_query(args: &QueryArgs) -> std::io::Result<()> {
+    if !std::path::Path::new(&args.db).exists() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "{}: no such database (record one with --sqlite-file)",
+                args.db
+            ),
+        ));
+    }
+    let conn = open(&args.db)?;
+    let since = args
+        .since_hours
+        .map(|h| chrono::Utc::now().timestamp() - h as i64 * 3600)
+        .unwrap_or(i64::MIN);
+
+    match args.summary {
+        QuerySummary::Worst => query_worst(&conn, since, args),
+        QuerySummary::Hourly => query_hourly(&conn, since, args),
+        QuerySummary::Incidents => query_incidents(&conn, since, args),
+    }
+    .map_err(sql_err)
+}
+
+/// Format an optional float for a table cell.
+fn cell(value: Option<f64>, precision: usize) -> String {
+    value
+        .map(|v| format!("{:.*}", precision, v))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+/// Format a Unix timestamp as UTC.
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Intervals ranked by their worst average pressure stall.
+fn query_worst(conn: &Connection, since: i64, args: &QueryArgs) -> rusqlite::Result<()> {
+    let bucket = (args.bucket_minutes.max(1) * 60) as i64;
+    let mut stmt = conn.prepare(
+        "SELECT (timestamp / ?1) * ?1 AS start,
+                COUNT(*),
+                AVG(cpu_usage_percent),
+                AVG(cpu_pressure_some_avg10),
+                AVG(mem_pressure_some_avg10),
+                AVG(io_pressure_some_avg10),
+                MAX(max_temp_celsius),
+                (SELECT COUNT(*) FROM recommendations r
+                   JOIN samples s ON s.id = r.sample_id
+                  WHERE s.timestamp / ?1 = samples.timestamp / ?1
+                    AND r.severity = 'critical') AS criticals,
+                MAX(COALESCE(AVG(cpu_pressure_some_avg10), 0),
+                    COALESCE(AVG(mem_pressure_some_avg10), 0),
+                    COALESCE(AVG(io_pressure_some_avg10), 0)) AS stall
+           FROM samples
+          WHERE timestamp >= ?2
+          GROUP BY start
+          ORDER BY stall DESC, criticals DESC
+          LIMIT ?3",
+    )?;
+    let rows = stmt.query_map(params![bucket, since, args.limit as i64], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+            row.get::<_, Option<f64>>(4)?,
+            row.get::<_, Option<f64>>(5)?,
+            row.get::<_, Option<f64>>(6)?,
+            row.get::<_, i64>(7)?,
+        ))
+    })?;
+
+    println!(
+        "Worst {}-minute intervals by pressure stall (UTC)",
+        args.bucket_minutes.max(1)
+    );
+    println!(
+        "{:<16} {:>7} {:>6} {:>7} {:>7} {:>7} {:>7} {:>5}",
+        "start", "samples", "cpu%", "cpuPSI", "memPSI", "ioPSI", "maxTemp", "crit"
+    );
+    for row in rows {
+        let (start, count, cpu, cpu_psi, mem_psi, io_psi, temp, criticals) = row?;
+        println!(
+            "{:<16} {:>7} {:>6} {:>7} {:>7} {:>7} {:>7} {:>5}",
+            format_time(start),
+            count,
+            cell(cpu, 1),
+            cell(cpu_psi, 1),
+            cell(mem_psi, 1),
+            cell(io_psi, 1),
+            cell(temp, 0),
+            criticals
+        );
+    }
+    Ok(())
+}
+
+/// Averages per hour.
+fn query_hourly(conn: &Connection, since: i64, args: &QueryArgs) -> rusqlite::Result<()> {
+    // Take the most recent hours, then print them oldest first
+    let mut stmt = conn.prepare(
+        "SELECT * FROM (
+             SELECT (timestamp / 3600) * 3600 AS hour,
+                    COUNT(*),
+                    AVG(cpu_usage_percent),
+                    AVG(load_avg_1),
+                    AVG(mem_used_mb),
+                    AVG(io_pressure_some_avg10),
+                    AVG(io_read_mb_per_sec),
+                    AVG(compute_duration_ms),
+                    MAX(max_temp_celsius)
+               FROM samples
+              WHERE timestamp >= ?1
+              GROUP BY hour
+              ORDER BY hour DESC
+              LIMIT ?2)
+          ORDER BY hour",
+    )?;
+    let rows = stmt.query_map(params![since, args.limit as i64], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, i64>(1)?,
+            row.get::<_, Option<f64>>(2)?,
+            row.get::<_, Option<f64>>(3)?,
+            row.get::<_, Option<f64>>(4)?,
+            row.get::<_, Option<f64>>(5)?,
+            row.get::<_, Option<f64>>(6)?,
+            row.get::<_, Option<f64>>(7)?,
+            row.get::<_, Option<f64>>(8)?,
+        ))
+    })?;
+
+    println!("Hourly averages (UTC)");
+    println!(
+        "{:<16} {:>7} {:>6} {:>6} {:>8} {:>6} {:>8} {:>9} {:>7}",
+        "hour", "samples", "cpu%", "load1", "memMB", "ioPSI", "readMB/s", "computeMs", "maxTemp"
+    );
+    for row in rows {
+        let (hour, count, cpu, load, mem, io_psi, read, compute, temp) = row?;
+        println!(
+            "{:<16} {:>7} {:>6} {:>6} {:>8} {:>6} {:>8} {:>9} {:>7}",
+            format_time(hour),
+            count,
+            cell(cpu, 1),
+            cell(load, 2),
+            cell(mem, 0),
+            cell(io_psi, 1),
+            cell(read, 1),
+            cell(compute, 1),
+            cell(temp, 0)
+        );
+    }
+    Ok(())
+}
+
+/// A recommendation from the time it started until it cleared.
+struct Incident {
+    /// When the recommendation first appeared
+    start: i64,
+    /// When it cleared (None if still active at the end of the data)
+    end: Option<i64>,
+    /// Recommendation title
+    title: String,
+    /// Worst severity reached
+    severity: String,
+}
+
+/// Recommendation incidents, most recent first.
+fn query_incidents(conn: &Connection, since: i64, args: &QueryArgs) -> rusqlite::Result<()> {
+    let mut stmt = conn.prepare(
+        "SELECT timestamp, kind, title, severity, COALESCE(problem, title) FROM events
+          WHERE timestamp >= ?1
+          ORDER BY timestamp, id",
+    )?;
+    let rows = stmt.query_map(params![since], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?;
+
+    let mut open: BTreeMap<String, Incident> = BTreeMap::new();
+    let mut incidents = Vec::new();
+    for row in rows {
+        let (timestamp, kind, title, severity, problem) = row?;
+        match kind.as_str() {
+            "started" => {
+                // A restart can leave an incident without a "cleared" event
+                if let Some(mut stale) = open.remove(&problem) {
+                    stale.end = Some(timestamp);
+                    incidents.push(stale);
+                }
+                open.insert(
+                    problem,
+                    Incident {
+                        start: timestamp,
+                        end: None,
+                        title,
+                        severity,
+                    },
+                );
+            }
+            "changed" => {
+                if let Some(incident) = open.get_mut(&problem) {
+                    if severity == Severity::Critical.name() {
+                        incident.severity = severity;
+                        incident.title = title;
+                    }
+                }
+            }
+            _ => {
+                if let Some(mut incident) = open.remove(&problem) {
+                    incident.end = Some(timestamp);
+                    incidents.push(incident);
+                }
+            }
+        }
+    }
+    incidents.extend(open.into_values());
+    incidents.sort_by_key(|i| std::cmp::Reverse(i.start));
+
+    println!("Incidents (UTC, most recent first)");
+    println!(
+        "{:<16} {:<16} {:>8} {:<8} title",
+        "start", "end", "duration", "severity"
+    );
+    for incident in incidents.iter().take(args.limit) {
+        let (end, duration) = match incident.end {
+            Some(end) => (format_time(end), format_duration(end - incident.start)),
+            None => ("ongoing".to_string(), "-".to_string()),
+        };
+        println!(
+            "{:<16} {:<16} {:>8} {:<8} {}",
+            format_time(incident.start),
+            end,
+            duration,
+            incident.severity,
+            incident.title
+        );
+    }
+    Ok(())
+}
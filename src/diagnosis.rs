@@ -0,0 +1,512 @@
+//! Root-cause diagnosis for slow-rs.
+//!
+//! Recommendations report every symptom on its own. This module answers
+//! "why is it slow right now" by weighing the symptoms as evidence for a
+//! small set of [`Cause`]s and ranking them.
+//!
+//! Each observation contributes a weight between 0 and 1 (0.5 at the
+//! warning level of the signal, 0.9 from the critical level). The weights
+//! of a cause are combined as independent evidence,
+//! `1 - (1 - w1)(1 - w2)...`, so several moderate signals pointing the same
+//! way outrank a single one.
+//!
+//! Thermal throttle events reported by the kernel (core and package
+//! `thermal_throttle` counters, x86 only) count towards [`Cause::Thermal`].
+//! Where they aren't available, throttling is inferred: CPU-bound
+//! benchmarks getting slower while the CPU is hot or throttled count
+//! towards [`Cause::Thermal`] too. The same slowdown counts towards VM
+//! steal or CPU contention when those are present, and towards
+//! [`Cause::Software`] (power-saving settings, or nothing else explaining
+//! it) otherwise.
+
+use std::collections::VecDeque;
+
+use crate::anomaly::{detect_anomalies, Anomaly};
+use crate::metrics::{parse_labeled, Metrics};
+use crate::power::PowerInfo;
+use crate::recommendations::{compute_slowdown_ratio, latest_run};
+use crate::thresholds::{Severity, Thresholds};
+
+/// Causes with less confidence than this are not reported.
+pub const MIN_CONFIDENCE: f64 = 0.3;
+
+/// Tasks blocked on I/O (warning, critical).
+const BLOCKED_TASKS: (f64, f64) = (4.0, 16.0);
+/// Pages swapped in and out per sample (warning, critical).
+const SWAP_PAGES: (f64, f64) = (100.0, 2000.0);
+/// Major page faults per sample (warning, critical).
+const MAJOR_FAULTS: (f64, f64) = (100.0, 1000.0);
+/// CPU pressure, some avg10 in % (warning, critical).
+const CPU_PRESSURE: (f64, f64) = (20.0, 50.0);
+/// Runnable tasks per CPU, including slow-rs itself (warning, critical).
+const RUN_QUEUE: (f64, f64) = (2.0, 4.0);
+/// Hypervisor steal in % of CPU time (warning, critical).
+const STEAL: (f64, f64) = (5.0, 20.0);
+/// Steal in % above which a CPU slowdown is attributed to the hypervisor.
+const STEAL_NOTICEABLE: f64 = 2.0;
+/// Thermal throttle events (core + package) per sample (warning, critical).
+const THROTTLE_EVENTS: (f64, f64) = (1.0, 100.0);
+/// File descriptors in use, % of the system limit (warning, critical).
+const FD_USAGE: (f64, f64) = (80.0, 95.0);
+
+/// A likely reason for the system being slow.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Cause {
+    /// Storage is slow or saturated
+    DiskIo,
+    /// Not enough RAM: swapping, reclaim, page faults
+    Memory,
+    /// More runnable work than CPUs
+    CpuContention,
+    /// Overheating hardware, usually with CPU throttling
+    Thermal,
+    /// The hypervisor is taking CPU time away from this VM
+    VmSteal,
+    /// Slow without resource pressure: configuration, power saving or limits
+    Software,
+}
+
+impl Cause {
+    /// All causes, in display order for equal confidence.
+    const ALL: [Cause; 6] = [
+        Cause::DiskIo,
+        Cause::Memory,
+        Cause::CpuContention,
+        Cause::Thermal,
+        Cause::VmSteal,
+        Cause::Software,
+    ];
+
+    /// Human-readable name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Cause::DiskIo => "Disk I/O",
+            Cause::Memory => "Memory",
+            Cause::CpuContention => "CPU contention",
+            Cause::Thermal => "Thermal",
+            Cause::VmSteal => "VM steal",
+            Cause::Software => "Software",
+        }
+    }
+}
+
+/// A ranked cause with the observations supporting it.
+#[derive(Clone, Debug)]
+pub struct Diagnosis {
+    /// The suspected cause
+    pub cause: Cause,
+    /// How well the evidence supports the cause (0-1)
+    pub confidence: f64,
+    /// Supporting observations, strongest first
+    pub evidence: Vec<String>,
+}
+
+impl Diagnosis {
+    /// One-line summary, e.g. "Disk I/O 86% (I/O pressure 31%, iowait 24%)".
+    pub fn summary(&self) -> String {
+        format!(
+            "{} {:.0}% ({})",
+            self.cause.name(),
+            self.confidence * 100.0,
+            self.evidence.join(", ")
+        )
+    }
+}
+
+/// A single observation pointing at a cause.
+struct Finding {
+    cause: Cause,
+    weight: f64,
+    text: String,
+}
+
+/// Rank the likely causes of slowness for the latest sample.
+///
+/// `history` holds the samples leading up to (and usually including)
+/// `metrics`. Returns causes with at least [`MIN_CONFIDENCE`], most likely
+/// first; an empty list means nothing points at a problem.
+pub fn diagnose(
+    metrics: &Metrics,
+    history: &VecDeque<Metrics>,
+    thresholds: &Thresholds,
+) -> Vec<Diagnosis> {
+    let mut findings = Vec::new();
+    let mut add = |cause: Cause, weight: f64, text: String| {
+        if weight > 0.0 {
+            findings.push(Finding {
+                cause,
+                weight,
+                text,
+            });
+        }
+    };
+    let t = thresholds;
+
+    // Disk I/O
+    if let Some(io) = metrics.io_pressure_some_avg10 {
+        let weight = strength(io, t.io_pressure_warning, t.io_pressure_critical);
+        add(Cause::DiskIo, weight, format!("I/O pressure {:.0}%", io));
+    }
+    if let Some(iowait) = metrics.iowait_percent() {
+        let weight = strength(iowait, t.iowait_warning, t.iowait_critical);
+        add(Cause::DiskIo, weight, format!("iowait {:.0}%", iowait));
+    }
+    let blocked = metrics.procs_blocked as f64;
+    add(
+        Cause::DiskIo,
+        strength(blocked, BLOCKED_TASKS.0, BLOCKED_TASKS.1),
+        format!("{} tasks blocked on I/O", metrics.procs_blocked),
+    );
+    // Benchmarks are judged by their most recent run
+    let latency = |value_fn: fn(&Metrics) -> Option<f64>| {
+        value_fn(metrics).or_else(|| history.iter().rev().find_map(value_fn))
+    };
+    if let Some(ms) = latency(|m| m.iops_read_p99_us.map(|us| us / 1000.0)) {
+        let weight = strength(
+            ms,
+            t.io_latency_p99_warning_ms,
+            t.io_latency_p99_critical_ms,
+        );
+        add(
+            Cause::DiskIo,
+            weight,
+            format!("random read p99 {:.0} ms", ms),
+        );
+    }
+    if let Some(ms) = latency(|m| m.fsync_p99_ms) {
+        let weight = strength(
+            ms,
+            t.io_latency_p99_warning_ms,
+            t.io_latency_p99_critical_ms,
+        );
+        add(Cause::DiskIo, weight, format!("fsync p99 {:.0} ms", ms));
+    }
+
+    // Memory
+    if let Some(mem) = metrics.mem_pressure_some_avg10 {
+        let weight = strength(mem, t.mem_pressure_warning, t.mem_pressure_critical);
+        add(
+            Cause::Memory,
+            weight,
+            format!("memory pressure {:.0}%", mem),
+        );
+    }
+    let swapped = (metrics.pswpin + metrics.pswpout) as f64;
+    add(
+        Cause::Memory,
+        strength(swapped, SWAP_PAGES.0, SWAP_PAGES.1),
+        format!(
+            "swapping {} in / {} out pages",
+            metrics.pswpin, metrics.pswpout
+        ),
+    );
+    add(
+        Cause::Memory,
+        strength(metrics.pgmajfault as f64, MAJOR_FAULTS.0, MAJOR_FAULTS.1),
+        format!("{} major faults", metrics.pgmajfault),
+    );
+    if metrics.mem_total_mb > 0 {
+        let weight = strength_below(
+            metrics.mem_available_mb as f64,
+            t.memory_available_warning_mb as f64,
+            t.memory_available_critical_mb as f64,
+        );
+        add(
+            Cause::Memory,
+            weight,
+            format!("{} MB available", metrics.mem_available_mb),
+        );
+    }
+
+    // CPU contention
+    if let Some(cpu) = metrics.cpu_pressure_some_avg10 {
+        let weight = strength(cpu, CPU_PRESSURE.0, CPU_PRESSURE.1);
+        add(
+            Cause::CpuContention,
+            weight,
+            format!("CPU pressure {:.0}%", cpu),
+        );
+    }
+    let usage = metrics.cpu_usage_percent as f64;
+    add(
+        Cause::CpuContention,
+        strength(
+            usage,
+            t.cpu_usage_warning as f64,
+            t.cpu_usage_critical as f64,
+        ),
+        format!("CPU {:.0}% busy", usage),
+    );
+    if metrics.cpu_count > 0 {
+        let per_cpu = metrics.procs_running as f64 / metrics.cpu_count as f64;
+        add(
+            Cause::CpuContention,
+            strength(per_cpu, RUN_QUEUE.0, RUN_QUEUE.1),
+            format!(
+                "{} runnable tasks on {} CPUs",
+                metrics.procs_running, metrics.cpu_count
+            ),
+        );
+    }
+    if let Some(us) = latency(|m| m.wakeup_p99_us) {
+        let weight = strength(
+            us,
+            t.wakeup_latency_warning_us,
+            t.wakeup_latency_critical_us,
+        );
+        add(
+            Cause::CpuContention,
+            weight,
+            format!("wakeup p99 {:.0} µs", us),
+        );
+    }
+
+    // Thermal
+    let temps = [
+        (
+            "CPU",
+            metrics.cpu_temp_celsius,
+            t.cpu_temp_warning,
+            t.cpu_temp_critical,
+        ),
+        (
+            "RAM",
+            metrics.dimm_temp_max,
+            t.dimm_temp_warning,
+            t.dimm_temp_critical,
+        ),
+        (
+            "disk",
+            metrics.disk_temp_max,
+            t.disk_temp_warning,
+            t.disk_temp_critical,
+        ),
+    ];
+    for (name, temp, warning, critical) in temps {
+        if let Some(temp) = temp {
+            let weight = strength(temp, warning, critical);
+            add(Cause::Thermal, weight, format!("{} {:.0}°C", name, temp));
+        }
+    }
+    let throttles = match (metrics.cpu_core_throttles, metrics.cpu_package_throttles) {
+        (None, None) => None,
+        (core, package) => Some(core.unwrap_or(0) + package.unwrap_or(0)),
+    };
+    if let Some(events) = throttles {
+        add(
+            Cause::Thermal,
+            strength(events as f64, THROTTLE_EVENTS.0, THROTTLE_EVENTS.1),
+            format!("CPU throttled {} times", events),
+        );
+    }
+
+    // VM steal
+    let steal = metrics.steal_percent().unwrap_or(0.0);
+    add(
+        Cause::VmSteal,
+        strength(steal, STEAL.0, STEAL.1),
+        format!("steal {:.0}%", steal),
+    );
+
+    // Software
+    if metrics.fd_max > 0 {
+        let used = metrics.fd_allocated as f64 / metrics.fd_max as f64 * 100.0;
+        add(
+            Cause::Software,
+            strength(used, FD_USAGE.0, FD_USAGE.1),
+            format!("{:.0}% of file descriptors in use", used),
+        );
+    }
+
+    // Unusual values that stay within the static thresholds
+    let anomalies = detect_anomalies(metrics, history, thresholds);
+    for anomaly in &anomalies {
+        if anomaly.threshold_severity != Severity::Normal {
+            continue;
+        }
+        if let Some(cause) = anomaly_cause(anomaly.check.key) {
+            add(cause, anomaly_weight(anomaly), describe_anomaly(anomaly));
+        }
+    }
+
+    // Benchmark regressions: storage and memory benchmarks point at their
+    // subsystem, CPU-bound ones are attributed below
+    let mut slowdowns: Vec<(f64, String)> = Vec::new();
+    let compared = latest_run(metrics, history, |m| m.baseline_pct.is_some());
+    if let Some(ref pct) = compared.baseline_pct {
+        let factor = t.baseline_regression_factor;
+        for (name, pct) in parse_labeled(pct) {
+            // Judged by the shortfall, so running at full speed counts as 0
+            let weight = strength(
+                100.0 - pct,
+                100.0 - 100.0 / factor,
+                100.0 - 100.0 / (factor * factor),
+            );
+            let text = format!("{} at {:.0}% of baseline", name, pct);
+            match benchmark_cause(name) {
+                Some(cause) => add(cause, weight, text),
+                None if weight > 0.0 => slowdowns.push((weight, text)),
+                None => {}
+            }
+        }
+    }
+    if let Some(ratio) = compute_slowdown_ratio(history) {
+        let warning = t.benchmark_slowdown_warning;
+        let weight = strength(ratio - 1.0, warning - 1.0, warning * warning - 1.0);
+        if weight > 0.0 {
+            slowdowns.push((
+                weight,
+                format!("compute {:.0}% slower than earlier", (ratio - 1.0) * 100.0),
+            ));
+        }
+    }
+    for anomaly in &anomalies {
+        if anomaly.check.key == "compute" {
+            slowdowns.push((anomaly_weight(anomaly), describe_anomaly(anomaly)));
+        }
+    }
+
+    // Attribute the worst CPU slowdown to whatever explains it
+    if let Some((weight, text)) = slowdowns.into_iter().max_by(|a, b| a.0.total_cmp(&b.0)) {
+        let hot = metrics
+            .cpu_temp_celsius
+            .filter(|&temp| temp >= t.cpu_temp_warning);
+        let busy = findings.iter().any(|f| f.cause == Cause::CpuContention);
+        let unexplained = findings.is_empty();
+        let low_power = PowerInfo::low_power_reason(metrics);
+
+        let mut add = |cause: Cause, text: String| {
+            findings.push(Finding {
+                cause,
+                weight,
+                text,
+            })
+        };
+        if throttles.unwrap_or(0) > 0 {
+            add(Cause::Thermal, format!("{} while throttled", text));
+        } else if let Some(temp) = hot {
+            add(
+                Cause::Thermal,
+                format!("{} at {:.0}°C (likely throttling)", text, temp),
+            );
+        }
+        if steal >= STEAL_NOTICEABLE {
+            add(Cause::VmSteal, format!("{} with {:.0}% steal", text, steal));
+        }
+        if busy {
+            add(Cause::CpuContention, format!("{} while busy", text));
+        }
+        if let Some(reason) = low_power {
+            add(Cause::Software, format!("{} while {}", text, reason));
+        } else if unexplained {
+            add(
+                Cause::Software,
+                format!("{} without resource pressure", text),
+            );
+        }
+    }
+
+    rank(findings)
+}
+
+/// Combine the findings per cause and sort by confidence.
+fn rank(mut findings: Vec<Finding>) -> Vec<Diagnosis> {
+    findings.sort_by(|a, b| b.weight.total_cmp(&a.weight));
+
+    let mut diagnoses: Vec<Diagnosis> = Cause::ALL
+        .iter()
+        .filter_map(|&cause| {
+            let own: Vec<&Finding> = findings.iter().filter(|f| f.cause == cause).collect();
+            let doubt: f64 = own.iter().map(|f| 1.0 - f.weight).product();
+            let confidence = 1.0 - doubt;
+            (confidence >= MIN_CONFIDENCE).then(|| Diagnosis {
+                cause,
+                confidence,
+                evidence: own.iter().map(|f| f.text.clone()).collect(),
+            })
+        })
+        .collect();
+
+    diagnoses.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    diagnoses
+}
+
+/// How strongly a value indicates a problem when higher is worse.
+///
+/// Ramps from 0 at half the warning level to 0.5 at the warning level and
+/// 0.9 at the critical level.
+fn strength(value: f64, warning: f64, critical: f64) -> f64 {
+    let onset = warning / 2.0;
+    if value >= critical {
+        0.9
+    } else if value >= warning {
+        0.5 + 0.4 * (value - warning) / (critical - warning)
+    } else if value > onset {
+        0.5 * (value - onset) / (warning - onset)
+    } else {
+        0.0
+    }
+}
+
+/// Like [`strength`] for values where lower is worse.
+///
+/// Ramps from 0 at twice the warning level.
+fn strength_below(value: f64, warning: f64, critical: f64) -> f64 {
+    let onset = warning * 2.0;
+    if value <= critical {
+        0.9
+    } else if value <= warning {
+        0.5 + 0.4 * (warning - value) / (warning - critical)
+    } else if value < onset {
+        0.5 * (onset - value) / (onset - warning)
+    } else {
+        0.0
+    }
+}
+
+/// Evidence weight of an anomaly (weaker than a threshold being crossed).
+fn anomaly_weight(anomaly: &Anomaly) -> f64 {
+    match anomaly.severity {
+        Severity::Critical => 0.5,
+        _ => 0.3,
+    }
+}
+
+/// Short description of an anomaly as evidence.
+fn describe_anomaly(anomaly: &Anomaly) -> String {
+    let check = anomaly.check;
+    format!(
+        "unusual {} {:.0}{} (typically {:.0}{})",
+        check.label, anomaly.value, check.unit, anomaly.median, check.unit
+    )
+}
+
+/// Cause suggested by an unusual metric (see [`crate::anomaly::CHECKS`]).
+///
+/// The compute benchmark is attributed like other CPU slowdowns.
+fn anomaly_cause(key: &str) -> Option<Cause> {
+    match key {
+        "iowait" | "io_pressure" | "io_read" | "iops_read_p99" | "fsync_p99" => Some(Cause::DiskIo),
+        "mem_available" | "mem_pressure" | "major_faults" => Some(Cause::Memory),
+        "cpu_usage" | "cpu_pressure" | "wakeup_p99" => Some(Cause::CpuContention),
+        "cpu_temp" | "dimm_temp" | "disk_temp" => Some(Cause::Thermal),
+        _ => None,
+    }
+}
+
+/// Subsystem measured by a baseline benchmark (see [`crate::baseline`]).
+///
+/// None for CPU-bound benchmarks, whose slowdown has several possible causes.
+fn benchmark_cause(name: &str) -> Option<Cause> {
+    if name.starts_with("io_")
+        || name.starts_with("iops_")
+        || name.starts_with("fsync_")
+        || name.starts_with("meta_")
+    {
+        Some(Cause::DiskIo)
+    } else if name.starts_with("mem") {
+        Some(Cause::Memory)
+    } else {
+        None
+    }
+}
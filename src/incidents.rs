@@ -0,0 +1,317 @@
+//! Incident tracking for slow-rs.
+//!
+//! Recommendations are recomputed for every sample and forgotten, so a
+//! 30-second I/O stall at 3am leaves no trace outside the CSV. The
+//! [`IncidentTracker`] turns them into incidents: one opens when a kind of
+//! recommendation first fires, records the worst severity it reaches and
+//! closes once the recommendation has stayed clear for the recovery period
+//! (`--incident-recovery`). A recommendation that flaps within the recovery
+//! period stays a single incident.
+//!
+//! Recommendations are matched by [`Recommendation::kind`], so "Elevated
+//! I/O Pressure" escalating to "High I/O Pressure" is one incident, as is
+//! an unusual value (see [`crate::anomaly`]) that later crosses its static
+//! threshold.
+//!
+//! Incidents are kept in memory for the TUI timeline and the headless
+//! summary, and recorded as `events` in SQLite with `--sqlite-file` (see
+//! [`crate::database`]). With `--incident-file` they are also saved to a
+//! JSON file when one opens, escalates or closes, and on exit. Incidents
+//! still open when slow-rs stopped are closed at the last saved sample in
+//! which they fired when the file is loaded again. Failing to read or write
+//! the file is a warning, not an error: tracking carries on in memory.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::format_duration;
+use crate::recommendations::Recommendation;
+use crate::thresholds::Severity;
+
+/// Maximum number of incidents kept in the file (oldest are dropped).
+const MAX_STORED: usize = 1000;
+
+/// A problem from the first sample it was reported until it recovered.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Incident {
+    /// Sequential number, unique within the incident file
+    pub id: u64,
+    /// Recommendation kind ([`Recommendation::kind`])
+    pub kind: String,
+    /// Recommendation title at the peak severity
+    pub title: String,
+    /// Recommendation advice at the peak severity
+    pub advice: String,
+    /// Worst severity reached
+    pub peak_severity: Severity,
+    /// Unix timestamp of the first sample in which it fired
+    pub opened: i64,
+    /// Unix timestamp at which the peak severity was first reached
+    pub peaked: i64,
+    /// Unix timestamp of the last sample in which it fired
+    pub last_seen: i64,
+    /// Unix timestamp of the first sample without it, once the recovery
+    /// period has passed (None while ongoing)
+    pub closed: Option<i64>,
+    /// Number of samples in which it fired
+    pub samples: u64,
+    /// First sample without it since it last fired (pending recovery)
+    #[serde(skip)]
+    cleared: Option<i64>,
+}
+
+impl Incident {
+    /// Whether the incident is still ongoing.
+    pub fn is_open(&self) -> bool {
+        self.closed.is_none()
+    }
+
+    /// Seconds from opening until it closed, or until `now` if still open.
+    pub fn duration_secs(&self, now: i64) -> i64 {
+        (self.closed.unwrap_or(now) - self.opened).max(0)
+    }
+
+    /// One-line description, e.g. "#3 High I/O Pressure (critical, 2m 10s)".
+    pub fn summary(&self, now: i64) -> String {
+        format!(
+            "#{} {} ({}, {})",
+            self.id,
+            self.title,
+            self.peak_severity.name(),
+            format_duration(self.duration_secs(now))
+        )
+    }
+}
+
+/// What happened to an incident in the latest [`IncidentTracker::update`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IncidentEvent {
+    /// A recommendation fired that had no open incident
+    Opened,
+    /// An open incident reached a higher severity
+    Escalated,
+    /// The recommendation stayed clear for the recovery period
+    Closed,
+}
+
+impl IncidentEvent {
+    /// Lowercase verb, as printed in headless mode.
+    pub fn name(self) -> &'static str {
+        match self {
+            IncidentEvent::Opened => "opened",
+            IncidentEvent::Escalated => "escalated",
+            IncidentEvent::Closed => "closed",
+        }
+    }
+}
+
+/// Contents of the incident file.
+#[derive(Serialize, Deserialize, Default)]
+struct IncidentFile {
+    /// Incidents, oldest first
+    incidents: Vec<Incident>,
+}
+
+/// Opens, escalates and closes incidents from each sample's recommendations.
+pub struct IncidentTracker {
+    /// Incident file (None = in memory only)
+    path: Option<PathBuf>,
+    /// Seconds a recommendation must stay clear before its incident closes
+    recovery_secs: i64,
+    /// All known incidents, oldest first
+    incidents: Vec<Incident>,
+    /// Number of incidents at the front of `incidents` from earlier runs
+    earlier: usize,
+    /// Events from the latest update, with the incident they apply to
+    changes: Vec<(IncidentEvent, Incident)>,
+}
+
+impl IncidentTracker {
+    /// Create a tracker, loading the incident file if there is one.
+    ///
+    /// Incidents left open by an earlier run are closed at their last sample.
+    /// If the file exists but cannot be read, the tracker starts empty and
+    /// doesn't save (so the file isn't overwritten); the error is returned
+    /// alongside it.
+    pub fn open(path: Option<PathBuf>, recovery_secs: u64) -> (Self, Option<std::io::Error>) {
+        let mut tracker = Self {
+            path: None,
+            recovery_secs: recovery_secs as i64,
+            incidents: Vec::new(),
+            earlier: 0,
+            changes: Vec::new(),
+        };
+        let path = match path {
+            Some(path) => path,
+            None => return (tracker, None),
+        };
+
+        match load(&path) {
+            Ok(file) => {
+                tracker.incidents = file.incidents;
+                for incident in tracker.incidents.iter_mut().filter(|i| i.is_open()) {
+                    incident.closed = Some(incident.last_seen);
+                }
+                tracker.earlier = tracker.incidents.len();
+                tracker.path = Some(path);
+                (tracker, None)
+            }
+            Err(e) => (tracker, Some(e)),
+        }
+    }
+
+    /// Path of the incident file, if incidents are saved.
+    pub fn path(&self) -> Option<&PathBuf> {
+        self.path.as_ref()
+    }
+
+    /// All incidents, oldest first.
+    pub fn incidents(&self) -> &[Incident] {
+        &self.incidents
+    }
+
+    /// Incidents opened since this tracker was created, oldest first.
+    pub fn session(&self) -> &[Incident] {
+        &self.incidents[self.earlier..]
+    }
+
+    /// Incidents opened, escalated or closed by the latest update.
+    pub fn changes(&self) -> &[(IncidentEvent, Incident)] {
+        &self.changes
+    }
+
+    /// Apply the recommendations of the sample taken at `timestamp`.
+    ///
+    /// The caller saves the incident file ([`Self::save`]) when this
+    /// reports [`Self::changes`].
+    pub fn update(&mut self, timestamp: i64, recommendations: &[Recommendation]) {
+        self.changes.clear();
+
+        // Worst recommendation of each kind
+        let mut firing: BTreeMap<&str, &Recommendation> = BTreeMap::new();
+        for rec in recommendations {
+            let worst = firing.entry(rec.kind).or_insert(rec);
+            if rec.severity > worst.severity {
+                *worst = rec;
+            }
+        }
+
+        for (kind, rec) in &firing {
+            match self
+                .incidents
+                .iter_mut()
+                .find(|i| i.is_open() && i.kind == *kind)
+            {
+                Some(incident) => {
+                    incident.last_seen = timestamp;
+                    incident.samples += 1;
+                    incident.cleared = None;
+                    if rec.severity > incident.peak_severity {
+                        incident.peak_severity = rec.severity;
+                        incident.peaked = timestamp;
+                        incident.title = rec.title.clone();
+                        incident.advice = rec.advice.clone();
+                        self.changes
+                            .push((IncidentEvent::Escalated, incident.clone()));
+                    }
+                }
+                None => {
+                    let incident = Incident {
+                        id: self.incidents.last().map_or(1, |i| i.id + 1),
+                        kind: kind.to_string(),
+                        title: rec.title.clone(),
+                        advice: rec.advice.clone(),
+                        peak_severity: rec.severity,
+                        opened: timestamp,
+                        peaked: timestamp,
+                        last_seen: timestamp,
+                        closed: None,
+                        samples: 1,
+                        cleared: None,
+                    };
+                    self.changes.push((IncidentEvent::Opened, incident.clone()));
+                    self.incidents.push(incident);
+                }
+            }
+        }
+
+        let recovery_secs = self.recovery_secs;
+        for incident in self.incidents.iter_mut().filter(|i| i.is_open()) {
+            if firing.contains_key(incident.kind.as_str()) {
+                continue;
+            }
+            let cleared = *incident.cleared.get_or_insert(timestamp);
+            if timestamp - cleared >= recovery_secs {
+                incident.closed = Some(cleared);
+                self.changes.push((IncidentEvent::Closed, incident.clone()));
+            }
+        }
+
+        // Drop the oldest closed incidents beyond the limit
+        let excess = self.incidents.len().saturating_sub(MAX_STORED);
+        if excess > 0 {
+            let earlier = self.earlier;
+            let (mut position, mut dropped, mut dropped_earlier) = (0, 0, 0);
+            self.incidents.retain(|i| {
+                let drop = dropped < excess && !i.is_open();
+                if drop {
+                    dropped += 1;
+                    if position < earlier {
+                        dropped_earlier += 1;
+                    }
+                }
+                position += 1;
+                !drop
+            });
+            self.earlier -= dropped_earlier;
+        }
+    }
+
+    /// Save the incident file atomically (does nothing without
+    /// `--incident-file`).
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = match self.path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+        let file = IncidentFile {
+            incidents: self.incidents.clone(),
+        };
+        write_atomic(path, &file)
+            .map_err(|e| std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))
+    }
+}
+
+/// Write the incident file to a temp file, then rename it into place.
+fn write_atomic(path: &std::path::Path, file: &IncidentFile) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_string_pretty(file)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Read the incident file, returning an empty one if it doesn't exist.
+fn load(path: &std::path::Path) -> std::io::Result<IncidentFile> {
+    let content = match std::fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(IncidentFile::default()),
+        Err(e) => {
+            return Err(std::io::Error::new(
+                e.kind(),
+                format!("{}: {}", path.display(), e),
+            ))
+        }
+    };
+    serde_json::from_str(&content).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    })
+}
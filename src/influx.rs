@@ -0,0 +1,422 @@
+//! InfluxDB line protocol output for slow-rs.
+//!
+//! Renders each [`Metrics`] sample as InfluxDB line protocol and writes it
+//! to a file, stdout or a UDP socket (e.g. Telegraf's `socket_listener` or
+//! InfluxDB's UDP listener), selected with `--influx-output`.
+//!
+//! # Measurements
+//!
+//! One measurement per subsystem, all tagged with `host`:
+//!
+//! - `cpu`: usage, time breakdown (jiffies), load, context switches
+//! - `mem`: memory, swap, meminfo details, paging and file descriptors
+//! - `disk` / `net`: per-device deltas (tag `device`), plus an
+//!   aggregate row with `device=all`
+//! - `psi`: pressure stall averages
+//! - `temps`: one row per sensor (tags `sensor` or `device`, and `source`)
+//! - `power`, `smart`, `ipmi`: state of the respective subsystem
+//! - `bench`: benchmark results of this sample, with `cpu`, `target` and
+//!   `benchmark` tags for per-item results
+
+use std::io::Write;
+use std::net::{ToSocketAddrs, UdpSocket};
+
+use crate::metrics::{parse_labeled, Metrics};
+use crate::prometheus::{BENCHMARK_GAUGES, BENCHMARK_LISTS};
+use crate::sinks::{open_output, MetricsSink};
+
+/// Maximum UDP payload; larger samples are split on line boundaries.
+const MAX_DATAGRAM: usize = 1400;
+
+/// A single line protocol point under construction.
+struct Line {
+    /// Measurement and tag set (already escaped)
+    key: String,
+    /// Field set (already escaped)
+    fields: Vec<String>,
+}
+
+impl Line {
+    /// Start a point for a measurement tagged with the host.
+    fn new(measurement: &str, host: &str) -> Self {
+        Self {
+            key: format!("{},host={}", measurement, escape_key(host)),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a tag (empty values are not allowed in line protocol).
+    fn tag(mut self, key: &str, value: &str) -> Self {
+        if !value.is_empty() {
+            self.key
+                .push_str(&format!(",{}={}", key, escape_key(value)));
+        }
+        self
+    }
+
+    /// Add a float field (skipped if None or not finite).
+    fn float(mut self, key: &str, value: Option<f64>) -> Self {
+        if let Some(value) = value.filter(|v| v.is_finite()) {
+            self.fields.push(format!("{}={}", key, value));
+        }
+        self
+    }
+
+    /// Add an integer field.
+    fn int(mut self, key: &str, value: u64) -> Self {
+        self.fields.push(format!("{}={}i", key, value));
+        self
+    }
+
+    /// Add a boolean field (skipped if None).
+    fn boolean(mut self, key: &str, value: Option<bool>) -> Self {
+        if let Some(value) = value {
+            self.fields.push(format!("{}={}", key, value));
+        }
+        self
+    }
+
+    /// Add a string field (skipped if None).
+    fn string(mut self, key: &str, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+            self.fields.push(format!("{}=\"{}\"", key, escaped));
+        }
+        self
+    }
+
+    /// Append the finished line to `out` (points without fields are dropped).
+    fn finish(self, out: &mut Vec<String>, timestamp_ns: i64) {
+        if !self.fields.is_empty() {
+            out.push(format!(
+                "{} {} {}",
+                self.key,
+                self.fields.join(","),
+                timestamp_ns
+            ));
+        }
+    }
+}
+
+/// Escape a tag value (commas, equals signs and spaces).
+fn escape_key(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+        .replace('\n', "\\n")
+}
+
+/// Render a sample as line protocol, one entry per line.
+pub fn render(m: &Metrics, host: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    let ts = m.timestamp * 1_000_000_000;
+
+    // === CPU ===
+    Line::new("cpu", host)
+        .float("usage_percent", Some(m.cpu_usage_percent as f64))
+        .int("count", m.cpu_count as u64)
+        .int("user", m.cpu_user)
+        .int("nice", m.cpu_nice)
+        .int("system", m.cpu_system)
+        .int("idle", m.cpu_idle)
+        .int("iowait", m.cpu_iowait)
+        .int("irq", m.cpu_irq)
+        .int("softirq", m.cpu_softirq)
+        .int("steal", m.cpu_steal)
+        .float("load_avg_1", Some(m.load_avg_1))
+        .float("load_avg_5", Some(m.load_avg_5))
+        .float("load_avg_15", Some(m.load_avg_15))
+        .int("context_switches", m.context_switches)
+        .int("interrupts", m.interrupts)
+        .int("process_count", m.process_count as u64)
+        .int("thread_count", m.thread_count)
+        .int("procs_running", m.procs_running)
+        .int("procs_blocked", m.procs_blocked)
+        .finish(&mut out, ts);
+
+    // === Memory ===
+    Line::new("mem", host)
+        .int("total_mb", m.mem_total_mb)
+        .int("used_mb", m.mem_used_mb)
+        .int("free_mb", m.mem_free_mb)
+        .int("available_mb", m.mem_available_mb)
+        .int("buffers_mb", m.mem_buffers_mb)
+        .int("cached_mb", m.mem_cached_mb)
+        .int("swap_total_mb", m.swap_total_mb)
+        .int("swap_used_mb", m.swap_used_mb)
+        .int("dirty_mb", m.dirty_mb)
+        .int("writeback_mb", m.writeback_mb)
+        .int("anon_pages_mb", m.anon_pages_mb)
+        .int("mapped_mb", m.mapped_mb)
+        .int("shmem_mb", m.shmem_mb)
+        .int("slab_mb", m.slab_mb)
+        .int("page_tables_mb", m.page_tables_mb)
+        .int("pgfault", m.pgfault)
+        .int("pgmajfault", m.pgmajfault)
+        .int("pgpgin", m.pgpgin)
+        .int("pgpgout", m.pgpgout)
+        .int("pswpin", m.pswpin)
+        .int("pswpout", m.pswpout)
+        .int("fd_allocated", m.fd_allocated)
+        .int("fd_max", m.fd_max)
+        .finish(&mut out, ts);
+
+    // === Disk (aggregate, then per device) ===
+    Line::new("disk", host)
+        .tag("device", "all")
+        .int("reads_completed", m.disk_reads_completed)
+        .int("reads_merged", m.disk_reads_merged)
+        .int("sectors_read", m.disk_sectors_read)
+        .int("read_time_ms", m.disk_read_time_ms)
+        .int("writes_completed", m.disk_writes_completed)
+        .int("writes_merged", m.disk_writes_merged)
+        .int("sectors_written", m.disk_sectors_written)
+        .int("write_time_ms", m.disk_write_time_ms)
+        .int("io_in_progress", m.disk_io_in_progress)
+        .int("io_time_ms", m.disk_io_time_ms)
+        .int("weighted_io_time_ms", m.disk_weighted_io_time_ms)
+        .finish(&mut out, ts);
+    for (device, d) in &m.disk_devices {
+        Line::new("disk", host)
+            .tag("device", device)
+            .int("reads_completed", d.reads_completed)
+            .int("reads_merged", d.reads_merged)
+            .int("sectors_read", d.sectors_read)
+            .int("read_time_ms", d.read_time_ms)
+            .int("writes_completed", d.writes_completed)
+            .int("writes_merged", d.writes_merged)
+            .int("sectors_written", d.sectors_written)
+            .int("write_time_ms", d.write_time_ms)
+            .int("io_in_progress", d.io_in_progress)
+            .int("io_time_ms", d.io_time_ms)
+            .int("weighted_io_time_ms", d.weighted_io_time_ms)
+            .finish(&mut out, ts);
+    }
+
+    // === Network (aggregate, then per interface) ===
+    Line::new("net", host)
+        .tag("device", "all")
+        .int("rx_bytes", m.net_rx_bytes)
+        .int("tx_bytes", m.net_tx_bytes)
+        .int("rx_packets", m.net_rx_packets)
+        .int("tx_packets", m.net_tx_packets)
+        .int("rx_errors", m.net_rx_errors)
+        .int("tx_errors", m.net_tx_errors)
+        .finish(&mut out, ts);
+    for (iface, n) in &m.net_interfaces {
+        Line::new("net", host)
+            .tag("device", iface)
+            .int("rx_bytes", n.rx_bytes)
+            .int("tx_bytes", n.tx_bytes)
+            .int("rx_packets", n.rx_packets)
+            .int("tx_packets", n.tx_packets)
+            .int("rx_errors", n.rx_errors)
+            .int("tx_errors", n.tx_errors)
+            .finish(&mut out, ts);
+    }
+
+    // === PSI ===
+    Line::new("psi", host)
+        .float("cpu_some_avg10", m.cpu_pressure_some_avg10)
+        .float("cpu_some_avg60", m.cpu_pressure_some_avg60)
+        .float("cpu_some_avg300", m.cpu_pressure_some_avg300)
+        .float("mem_some_avg10", m.mem_pressure_some_avg10)
+        .float("mem_some_avg60", m.mem_pressure_some_avg60)
+        .float("mem_full_avg10", m.mem_pressure_full_avg10)
+        .float("io_some_avg10", m.io_pressure_some_avg10)
+        .float("io_some_avg60", m.io_pressure_some_avg60)
+        .float("io_full_avg10", m.io_pressure_full_avg10)
+        .float("io_full_avg60", m.io_pressure_full_avg60)
+        .finish(&mut out, ts);
+
+    // === Temperatures (one row per sensor) ===
+    Line::new("temps", host)
+        .tag("sensor", "cpu")
+        .tag("source", m.cpu_temp_source.as_deref().unwrap_or_default())
+        .float("celsius", m.cpu_temp_celsius)
+        .finish(&mut out, ts);
+    Line::new("temps", host)
+        .tag("sensor", "max")
+        .float("celsius", m.max_temp_celsius)
+        .finish(&mut out, ts);
+    for (sensor, temp) in m
+        .dimm_temps
+        .as_deref()
+        .map(parse_labeled)
+        .unwrap_or_default()
+    {
+        Line::new("temps", host)
+            .tag("sensor", sensor)
+            .tag("source", m.dimm_temp_source.as_deref().unwrap_or_default())
+            .float("celsius", Some(temp))
+            .finish(&mut out, ts);
+    }
+    for (device, temp) in m
+        .disk_temps
+        .as_deref()
+        .map(parse_labeled)
+        .unwrap_or_default()
+    {
+        Line::new("temps", host)
+            .tag("device", device)
+            .tag("source", m.disk_temp_source.as_deref().unwrap_or_default())
+            .float("celsius", Some(temp))
+            .finish(&mut out, ts);
+    }
+    for t in &m.ipmi_temps {
+        Line::new("temps", host)
+            .tag("sensor", &t.name)
+            .tag("source", "ipmi")
+            .float("celsius", Some(t.temp_celsius))
+            .string("status", Some(&t.status))
+            .finish(&mut out, ts);
+    }
+
+    // === Power ===
+    Line::new("power", host)
+        .boolean("ac_online", m.ac_online)
+        .float("battery_capacity_percent", m.battery_capacity_percent)
+        .float("battery_power_watts", m.battery_power_watts)
+        .string("battery_status", m.battery_status.as_deref())
+        .string("cpufreq_governor", m.cpufreq_governor.as_deref())
+        .string("cpufreq_epp", m.cpufreq_epp.as_deref())
+        .string("platform_profile", m.platform_profile.as_deref())
+        .finish(&mut out, ts);
+
+    // === SMART ===
+    if m.smart_available == Some(true) {
+        let mut line =
+            Line::new("smart", host).boolean("health_all_passed", m.smart_health_all_passed);
+        if let Some(sectors) = m.smart_reallocated_sectors_total {
+            line = line.int("reallocated_sectors_total", sectors);
+        }
+        if let Some(sectors) = m.smart_pending_sectors_total {
+            line = line.int("pending_sectors_total", sectors);
+        }
+        line.finish(&mut out, ts);
+    }
+
+    // === IPMI ===
+    if m.ipmi_available == Some(true) {
+        Line::new("ipmi", host)
+            .float("dimm_temp_max", m.ipmi_dimm_temp_max)
+            .string("dimm_status", m.ipmi_dimm_status.as_deref())
+            .finish(&mut out, ts);
+        for dimm in &m.ipmi_dimm_temps {
+            Line::new("ipmi", host)
+                .tag("sensor", &dimm.name)
+                .float("dimm_celsius", Some(dimm.temp_celsius))
+                .string("status", Some(&dimm.status))
+                .finish(&mut out, ts);
+        }
+    }
+
+    // === Benchmarks (only those that ran in this sample) ===
+    let mut bench = Line::new("bench", host);
+    for (name, _, value_fn) in BENCHMARK_GAUGES {
+        bench = bench.float(name, value_fn(m));
+    }
+    bench.finish(&mut out, ts);
+    for (name, label, _, list_fn) in BENCHMARK_LISTS {
+        for (item, value) in list_fn(m).map(|l| parse_labeled(l)).unwrap_or_default() {
+            Line::new("bench", host)
+                .tag(label, item)
+                .float(name, Some(value))
+                .finish(&mut out, ts);
+        }
+    }
+
+    out
+}
+
+/// Where line protocol is written.
+enum Destination {
+    /// File (appended) or stdout
+    Writer(Box<dyn Write>),
+    /// Connected UDP socket
+    Udp(UdpSocket),
+}
+
+/// Writes samples as InfluxDB line protocol.
+pub struct InfluxSink {
+    /// Value of the `host` tag
+    host: String,
+    /// Output destination
+    destination: Destination,
+}
+
+impl InfluxSink {
+    /// Open a line protocol sink.
+    ///
+    /// `dest` is `udp://HOST:PORT`, `-` for stdout, or a file path (appended).
+    pub fn open(dest: &str, host: &str) -> std::io::Result<Self> {
+        let destination = if let Some(addr) = dest.strip_prefix("udp://") {
+            let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+                std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("cannot resolve {}", addr),
+                )
+            })?;
+            let bind = if addr.is_ipv4() {
+                "0.0.0.0:0"
+            } else {
+                "[::]:0"
+            };
+            let socket = UdpSocket::bind(bind)?;
+            socket.connect(addr)?;
+            Destination::Udp(socket)
+        } else {
+            Destination::Writer(open_output(dest)?)
+        };
+
+        Ok(Self {
+            host: host.to_string(),
+            destination,
+        })
+    }
+}
+
+impl MetricsSink for InfluxSink {
+    fn name(&self) -> &'static str {
+        "InfluxDB output"
+    }
+
+    fn write(&mut self, metrics: &Metrics) -> std::io::Result<()> {
+        let lines = render(metrics, &self.host);
+
+        match self.destination {
+            Destination::Writer(ref mut writer) => {
+                for line in &lines {
+                    writeln!(writer, "{}", line)?;
+                }
+                writer.flush()
+            }
+            Destination::Udp(ref socket) => {
+                // Pack whole lines into datagrams below the MTU
+                let mut datagram = String::new();
+                for line in &lines {
+                    if !datagram.is_empty() && datagram.len() + line.len() + 1 > MAX_DATAGRAM {
+                        send_datagram(socket, &datagram)?;
+                        datagram.clear();
+                    }
+                    datagram.push_str(line);
+                    datagram.push('\n');
+                }
+                if !datagram.is_empty() {
+                    send_datagram(socket, &datagram)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Send one datagram. A missing listener is not an error (UDP is fire-and-forget).
+fn send_datagram(socket: &UdpSocket, datagram: &str) -> std::io::Result<()> {
+    match socket.send(datagram.as_bytes()) {
+        Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => Ok(()),
+        result => result.map(|_| ()),
+    }
+}
@@ -42,19 +42,45 @@
 //!
 //! - [`config`]: CLI argument parsing and configuration
 //! - [`metrics`]: Data structures for collected metrics
+//! - [`csvlog`]: CSV log with schema checking
 //! - [`collectors`]: Functions to read system stats from `/proc`
+//! - [`power`]: Battery, AC and power-profile state from `/sys`
 //! - [`benchmarks`]: Active performance tests
+//! - [`baseline`]: Known-good benchmark baselines and regression detection
+//! - [`anomaly`]: Robust z-score anomaly detection on metric history
+//! - [`diagnosis`]: Ranking of likely root causes from all signals
+//! - [`incidents`]: Incident tracking and the incident history file
+//! - [`prometheus`]: Prometheus text exposition and `/metrics` exporter
+//! - [`sinks`]: Structured output sinks (JSON Lines)
+//! - [`influx`]: InfluxDB line protocol sink
+//! - [`database`]: SQLite storage and `slow-rs query` summaries
+//! - [`replay`]: Loading and playing back recordings (`slow-rs replay`)
+//! - [`report`]: Markdown/HTML reports from recordings (`slow-rs report`)
+//! - [`compare`]: Before/after comparison of two recordings (`slow-rs compare`)
 //! - [`app`]: Main application state and coordination
 //! - [`ui`]: Terminal user interface
 
+mod anomaly;
 mod app;
 mod availability;
+mod baseline;
 mod benchmarks;
 mod collectors;
+mod compare;
 mod config;
+mod csvlog;
+mod database;
+mod diagnosis;
+mod incidents;
+mod influx;
 mod ipmi;
 mod metrics;
+mod power;
+mod prometheus;
 mod recommendations;
+mod replay;
+mod report;
+mod sinks;
 mod smart;
 mod thresholds;
 mod ui;
@@ -66,9 +92,18 @@ use std::time::Duration;
 use clap::Parser;
 
 use app::App;
-use config::Config;
+use config::{Command, Config, ReplayArgs};
+use thresholds::Thresholds;
 
-fn main() -> std::io::Result<()> {
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+/// Parse the command line and run the requested mode.
+fn run() -> std::io::Result<()> {
     // Platform check - warn on non-Linux systems
     #[cfg(not(target_os = "linux"))]
     {
@@ -85,6 +120,15 @@ fn main() -> std::io::Result<()> {
     }
 
     let config = Config::parse();
+
+    match config.command {
+        Some(Command::Query(ref args)) => return database::run_query(args),
+        Some(Command::Replay(ref args)) => return run_replay(&config, args),
+        Some(Command::Report(ref args)) => return report::run(args, &config),
+        Some(Command::Compare(ref args)) => return compare::run(args),
+        None => {}
+    }
+
     let app = App::new(config.clone())?;
 
     // Create test file if needed
@@ -97,9 +141,11 @@ fn main() -> std::io::Result<()> {
     let interval = Duration::from_secs(config.interval);
 
     // Check if stdout is a TTY - if not, force headless mode
-    let use_headless = config.headless || !is_terminal();
+    let use_headless = config.headless || !is_terminal() || config.sink_uses_stdout();
     if !config.headless && !is_terminal() {
         eprintln!("Warning: stdout is not a TTY, running in headless mode");
+    } else if !config.headless && config.sink_uses_stdout() {
+        eprintln!("Warning: samples are written to stdout, running in headless mode");
     }
 
     if use_headless {
@@ -111,6 +157,24 @@ fn main() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Load a recording and play it back in the TUI.
+fn run_replay(config: &Config, args: &ReplayArgs) -> std::io::Result<()> {
+    if !is_terminal() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "replay needs a terminal (stdout is not a TTY)",
+        ));
+    }
+
+    let samples = replay::load(&args.file)?;
+    let playback = replay::Playback::new(samples, args.speed, config.history_size);
+    let thresholds = Thresholds::from_config(config);
+
+    let running = Arc::new(AtomicBool::new(true));
+    setup_signal_handler(running.clone());
+    ui::run_replay(playback, &thresholds, running)
+}
+
 /// Global flag for signal handler (must be static for signal safety).
 static SIGNAL_RECEIVED: AtomicBool = AtomicBool::new(false);
 
@@ -4,11 +4,24 @@
 //! system performance data, as well as intermediate data structures used
 //! during collection.
 
-use serde::Serialize;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::collectors::{DiskStats, NetStats};
+
+/// Version of the CSV column layout, written as the first column.
+///
+/// Bump whenever a [`Metrics`] field is added, removed, renamed or
+/// reordered. Files written before versioning have no `schema_version`
+/// column and count as version 1.
+pub const SCHEMA_VERSION: u32 = 3;
 
 /// Complete snapshot of system metrics at a point in time.
 ///
-/// This struct is serialized to CSV for logging. All fields are designed
+/// This struct is serialized to CSV for logging, so every serialized
+/// field must be a scalar and always present (a skipped field would shift
+/// the columns; see [`SCHEMA_VERSION`]). All fields are designed
 /// to help diagnose system slowdowns by providing visibility into various
 /// subsystems.
 ///
@@ -16,15 +29,26 @@ use serde::Serialize;
 ///
 /// - **Timestamps**: When the measurement was taken
 /// - **Benchmarks**: Active performance tests (I/O, compute, memory)
+/// - **Per-Target I/O**: Disk benchmarks for each `--io-target`
+/// - **Baseline**: Benchmarks as a percentage of a recorded known-good run
 /// - **Memory**: RAM and swap usage details
 /// - **CPU**: Usage percentages and time breakdowns
 /// - **Disk I/O**: Read/write statistics from the kernel
 /// - **Network**: Traffic and error counters
 /// - **Pressure (PSI)**: Linux pressure stall information
 /// - **Temperatures**: Hardware thermal sensors
+/// - **Power**: Battery/AC state, cpufreq governor and platform profile
 /// - **VM Stats**: Virtual memory and paging statistics
-#[derive(Serialize, Clone, Debug, Default)]
+/// - **Per-Device**: Disk and network deltas per device (not in the CSV)
+///
+/// Recorded samples are deserialized again by `slow-rs replay`; fields
+/// missing from older recordings take their default value.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
 pub struct Metrics {
+    /// CSV column layout version ([`SCHEMA_VERSION`])
+    pub schema_version: u32,
+
     // ===== Timestamps =====
     /// Unix timestamp (seconds since epoch)
     pub timestamp: i64,
@@ -38,10 +62,125 @@ pub struct Metrics {
     pub io_write_mb_per_sec: Option<f64>,
     /// Time to read test file + compute SHA256 in milliseconds
     pub sha256_duration_ms: Option<f64>,
-    /// Time to allocate and touch 64MB of memory in milliseconds
-    pub memory_alloc_duration_ms: f64,
-    /// Time to compute 10 rounds of SHA256 on 1MB data in milliseconds
-    pub compute_duration_ms: f64,
+    /// Random 4K read IOPS (None if IOPS benchmark disabled)
+    pub iops_read: Option<f64>,
+    /// Random 4K read median latency in microseconds
+    pub iops_read_p50_us: Option<f64>,
+    /// Random 4K read 99th percentile latency in microseconds
+    pub iops_read_p99_us: Option<f64>,
+    /// Random 4K read 99.9th percentile latency in microseconds
+    pub iops_read_p999_us: Option<f64>,
+    /// Random 4K write IOPS
+    pub iops_write: Option<f64>,
+    /// Random 4K write median latency in microseconds
+    pub iops_write_p50_us: Option<f64>,
+    /// Random 4K write 99th percentile latency in microseconds
+    pub iops_write_p99_us: Option<f64>,
+    /// Random 4K write 99.9th percentile latency in microseconds
+    pub iops_write_p999_us: Option<f64>,
+    /// Whether the IOPS benchmark bypassed the page cache with O_DIRECT
+    pub iops_direct_io: Option<bool>,
+    /// Median write+fsync latency in milliseconds (None if fsync benchmark disabled)
+    pub fsync_p50_ms: Option<f64>,
+    /// 99th percentile write+fsync latency in milliseconds
+    pub fsync_p99_ms: Option<f64>,
+    /// Maximum write+fsync latency in milliseconds
+    pub fsync_max_ms: Option<f64>,
+    /// Median write+fdatasync latency in milliseconds
+    pub fdatasync_p50_ms: Option<f64>,
+    /// 99th percentile write+fdatasync latency in milliseconds
+    pub fdatasync_p99_ms: Option<f64>,
+    /// Maximum write+fdatasync latency in milliseconds
+    pub fdatasync_max_ms: Option<f64>,
+    /// File creations per second (None if metadata benchmark disabled)
+    pub meta_create_ops_per_sec: Option<f64>,
+    /// stat calls per second
+    pub meta_stat_ops_per_sec: Option<f64>,
+    /// Renames per second
+    pub meta_rename_ops_per_sec: Option<f64>,
+    /// Unlinks per second
+    pub meta_unlink_ops_per_sec: Option<f64>,
+    /// Minimum scheduler wakeup delay in microseconds (None if wakeup benchmark disabled)
+    pub wakeup_min_us: Option<f64>,
+    /// Average scheduler wakeup delay in microseconds
+    pub wakeup_avg_us: Option<f64>,
+    /// Maximum scheduler wakeup delay in microseconds
+    pub wakeup_max_us: Option<f64>,
+    /// 99th percentile scheduler wakeup delay in microseconds
+    pub wakeup_p99_us: Option<f64>,
+    /// Time to allocate and touch 64MB of memory in milliseconds (None if not due)
+    pub memory_alloc_duration_ms: Option<f64>,
+    /// Time to compute 10 rounds of SHA256 on 1MB data in milliseconds (None if not due)
+    pub compute_duration_ms: Option<f64>,
+    /// Per-core compute times as comma-separated string (e.g., "0:38.1,1:37.9")
+    pub core_compute_ms: Option<String>,
+    /// CPU with the fastest single-core compute time
+    pub core_compute_fastest_cpu: Option<usize>,
+    /// Fastest single-core compute time in milliseconds
+    pub core_compute_fastest_ms: Option<f64>,
+    /// CPU with the slowest single-core compute time
+    pub core_compute_slowest_cpu: Option<usize>,
+    /// Slowest single-core compute time in milliseconds
+    pub core_compute_slowest_ms: Option<f64>,
+    /// Wall time to run the compute kernel on all cores at once in milliseconds
+    pub all_core_compute_ms: Option<f64>,
+    /// Multi-core scaling efficiency in percent, relative to the physical
+    /// cores (100 = perfect scaling; above 100 = SMT siblings added throughput)
+    pub compute_scaling_efficiency: Option<f64>,
+    /// CPUs much slower than their siblings (e.g., "3,7")
+    pub slow_cores: Option<String>,
+    /// STREAM copy bandwidth in GB/s (None if memory benchmark disabled)
+    pub mem_copy_gb_per_sec: Option<f64>,
+    /// STREAM scale bandwidth in GB/s
+    pub mem_scale_gb_per_sec: Option<f64>,
+    /// STREAM add bandwidth in GB/s
+    pub mem_add_gb_per_sec: Option<f64>,
+    /// STREAM triad bandwidth in GB/s
+    pub mem_triad_gb_per_sec: Option<f64>,
+    /// Pointer-chase latency with an L1-sized working set in ns
+    pub mem_latency_l1_ns: Option<f64>,
+    /// Pointer-chase latency with an L2-sized working set in ns
+    pub mem_latency_l2_ns: Option<f64>,
+    /// Pointer-chase latency with an LLC-sized working set in ns
+    pub mem_latency_llc_ns: Option<f64>,
+    /// Pointer-chase latency with a DRAM-sized working set in ns
+    pub mem_latency_dram_ns: Option<f64>,
+
+    // ===== Benchmark Schedule =====
+    /// Benchmarks that ran in this sample (e.g., "alloc,compute,io"); others are empty
+    pub benchmarks_ran: Option<String>,
+
+    // ===== Adaptive Benchmarking (--adaptive) =====
+    /// Backoff applied to heavy benchmarks ("reduced" or "skipped"; None = full run)
+    pub bench_backoff: Option<String>,
+    /// Stress readings that triggered the backoff (e.g., "io_pressure=31.2% iowait=22.0%")
+    pub bench_backoff_reason: Option<String>,
+    /// Enabled benchmarks that were skipped this sample (e.g., "io,iops,fsync")
+    pub benchmarks_skipped: Option<String>,
+
+    // ===== Per-Target I/O (--io-target, "path:value" lists) =====
+    /// Sequential read MB/s per target (e.g., "/:512.3,/home:220.1")
+    pub target_read_mb_per_sec: Option<String>,
+    /// Sequential write MB/s per target
+    pub target_write_mb_per_sec: Option<String>,
+    /// Random 4K read IOPS per target
+    pub target_iops_read: Option<String>,
+    /// Random 4K read p99 latency in µs per target
+    pub target_iops_read_p99_us: Option<String>,
+    /// Random 4K write IOPS per target
+    pub target_iops_write: Option<String>,
+    /// fsync p99 latency in ms per target
+    pub target_fsync_p99_ms: Option<String>,
+    /// Metadata file creates per second per target
+    pub target_meta_create_ops_per_sec: Option<String>,
+
+    // ===== Baseline Comparison =====
+    /// Benchmark performance as % of baseline (e.g., "compute_duration_ms:98,iops_read:45")
+    pub baseline_pct: Option<String>,
+    /// Lowest % of baseline across all benchmarks (100 = as fast as baseline)
+    pub baseline_worst_pct: Option<f64>,
+    /// Benchmarks regressed beyond the configured factor (e.g., "iops_read:45")
+    pub baseline_regressions: Option<String>,
 
     // ===== Memory (from sysinfo + /proc/meminfo) =====
     /// Total physical RAM in MB
@@ -184,6 +323,10 @@ pub struct Metrics {
     pub disk_temp_source: Option<String>,
     /// Maximum disk temperature in Celsius (from NVMe or SMART)
     pub disk_temp_max: Option<f64>,
+    /// Core thermal throttle events since the previous sample (x86 only)
+    pub cpu_core_throttles: Option<u64>,
+    /// Package thermal throttle events since the previous sample (x86 only)
+    pub cpu_package_throttles: Option<u64>,
 
     // ===== Context Switches and Interrupts (delta) =====
     /// Number of context switches
@@ -231,43 +374,96 @@ pub struct Metrics {
     /// System uptime in seconds
     pub uptime_secs: f64,
 
+    // ===== Power (from /sys/class/power_supply and cpufreq) =====
+    /// Whether AC power is connected (None if no AC adapter is reported)
+    pub ac_online: Option<bool>,
+    /// Battery charge level in percent
+    pub battery_capacity_percent: Option<f64>,
+    /// Battery status (e.g., "Charging", "Discharging", "Full")
+    pub battery_status: Option<String>,
+    /// Battery charge/discharge rate in watts
+    pub battery_power_watts: Option<f64>,
+    /// Active cpufreq scaling governor (e.g., "powersave", "performance")
+    pub cpufreq_governor: Option<String>,
+    /// cpufreq energy/performance preference (intel_pstate / amd-pstate)
+    pub cpufreq_epp: Option<String>,
+    /// ACPI platform profile (e.g., "low-power", "balanced", "performance")
+    pub platform_profile: Option<String>,
+
     // ===== SMART Health =====
     /// Whether SMART data is available
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub smart_available: Option<bool>,
     /// Whether all disks passed health check
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub smart_health_all_passed: Option<bool>,
     /// Total reallocated sectors across all disks
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub smart_reallocated_sectors_total: Option<u64>,
     /// Total pending sectors across all disks
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub smart_pending_sectors_total: Option<u64>,
 
     // ===== IPMI Sensors =====
     /// Whether IPMI data is available
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub ipmi_available: Option<bool>,
     /// IPMI DIMM temperature (max across all DIMMs)
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub ipmi_dimm_temp_max: Option<f64>,
     /// IPMI DIMM status (ok, nc, cr, nr)
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub ipmi_dimm_status: Option<String>,
     /// Detailed IPMI DIMM info (e.g., "DIMMC1:99°C[NR], DIMMD1:100°C[NR]")
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub ipmi_dimm_details: Option<String>,
-    /// Individual IPMI DIMM temperatures for plotting
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    /// Individual IPMI DIMM temperatures for plotting (structured sinks only, not in the CSV)
+    #[serde(skip_serializing)]
     pub ipmi_dimm_temps: Vec<IpmiDimmTemp>,
-    /// All IPMI temperature sensors (CPU, system, etc.)
-    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    /// All IPMI temperature sensors (structured sinks only, not in the CSV)
+    #[serde(skip_serializing)]
     pub ipmi_temps: Vec<IpmiTempReading>,
+
+    // ===== Per-Device =====
+    /// Per-disk I/O deltas, keyed by device (structured sinks only, not in the CSV)
+    #[serde(skip_serializing)]
+    pub disk_devices: BTreeMap<String, DiskStats>,
+    /// Per-interface network deltas, keyed by interface (structured sinks only, not in the CSV)
+    #[serde(skip_serializing)]
+    pub net_interfaces: BTreeMap<String, NetStats>,
+}
+
+impl Metrics {
+    /// Share of CPU time spent waiting for I/O in this sample (0-100%).
+    pub fn iowait_percent(&self) -> Option<f64> {
+        let total = self.cpu_user + self.cpu_system + self.cpu_idle + self.cpu_iowait;
+        (total > 0).then(|| self.cpu_iowait as f64 / total as f64 * 100.0)
+    }
+
+    /// Share of CPU time stolen by the hypervisor in this sample (0-100%).
+    pub fn steal_percent(&self) -> Option<f64> {
+        let total = self.cpu_user
+            + self.cpu_nice
+            + self.cpu_system
+            + self.cpu_idle
+            + self.cpu_iowait
+            + self.cpu_irq
+            + self.cpu_softirq
+            + self.cpu_steal;
+        (total > 0).then(|| self.cpu_steal as f64 / total as f64 * 100.0)
+    }
+
+    /// Whether heavy benchmarks ran with reduced workloads in this sample
+    /// (`bench_backoff` = "reduced").
+    pub fn benchmarks_reduced(&self) -> bool {
+        self.bench_backoff.as_deref() == Some("reduced")
+    }
+
+    /// `value`, or None if this sample's heavy benchmarks were reduced.
+    ///
+    /// A quarter-size run (test file, fsync count, IOPS phase, STREAM arrays
+    /// that may fit in cache) isn't comparable with a full run, so anomaly
+    /// detection, `compare` and `report` pass shrinkable benchmark results
+    /// through this.
+    pub fn unreduced(&self, value: Option<f64>) -> Option<f64> {
+        value.filter(|_| !self.benchmarks_reduced())
+    }
 }
 
 /// Individual DIMM temperature from IPMI.
-#[derive(Serialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct IpmiDimmTemp {
     /// Sensor name (e.g., "DIMMA1", "P1-DIMMC1")
     pub name: String,
@@ -278,7 +474,7 @@ pub struct IpmiDimmTemp {
 }
 
 /// General temperature reading from IPMI.
-#[derive(Serialize, Clone, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct IpmiTempReading {
     /// Sensor name (e.g., "CPU Temp", "System Temp")
     pub name: String,
@@ -287,3 +483,40 @@ pub struct IpmiTempReading {
     /// Sensor status
     pub status: String,
 }
+
+/// Parse a "label:value,label:value" list as stored in per-target fields.
+///
+/// Labels may themselves contain ':' (only the last one separates the value).
+pub fn parse_labeled(list: &str) -> Vec<(&str, f64)> {
+    list.split(',')
+        .filter_map(|item| {
+            let (label, value) = item.rsplit_once(':')?;
+            Some((label, value.parse().ok()?))
+        })
+        .collect()
+}
+
+/// Format a duration in seconds as e.g. "35s", "4m 10s" or "2h 05m".
+///
+/// Shared by `query`, `report` and the incident timeline so they agree.
+pub fn format_duration(secs: i64) -> String {
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, secs % 3600 / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Format a value with precision suited to its magnitude (as in `report`
+/// and `compare` tables).
+pub fn format_value(value: f64) -> String {
+    if value.abs() >= 100.0 {
+        format!("{:.0}", value)
+    } else if value.abs() >= 10.0 {
+        format!("{:.1}", value)
+    } else {
+        format!("{:.2}", value)
+    }
+}
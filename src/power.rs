@@ -0,0 +1,160 @@
+//! Power supply and power-management state for slow-rs.
+//!
+//! This module reads battery/AC state from `/sys/class/power_supply/*`
+//! together with the active cpufreq governor and ACPI platform profile.
+//! On laptops a large class of "slowness" is simply the machine running
+//! on battery in a power-saving mode, so these are collected alongside
+//! the thermal and pressure metrics.
+//!
+//! # Data Sources
+//!
+//! - `/sys/class/power_supply/*/{type,online,capacity,status,power_now}`
+//! - `/sys/devices/system/cpu/cpu0/cpufreq/{scaling_governor,energy_performance_preference}`
+//! - `/sys/firmware/acpi/platform_profile`
+
+use std::path::Path;
+
+use crate::metrics::Metrics;
+
+/// Power supply and power-management information.
+#[derive(Clone, Debug, Default)]
+pub struct PowerInfo {
+    /// Whether any AC adapter reports being online (None if no adapter found)
+    pub ac_online: Option<bool>,
+    /// Battery charge level in percent (average across batteries)
+    pub battery_capacity_percent: Option<f64>,
+    /// Battery status (e.g., "Charging", "Discharging", "Full")
+    pub battery_status: Option<String>,
+    /// Battery charge/discharge rate in watts (summed across batteries)
+    pub battery_power_watts: Option<f64>,
+    /// Active cpufreq scaling governor (e.g., "powersave", "performance")
+    pub cpufreq_governor: Option<String>,
+    /// Energy/performance preference hint (intel_pstate / amd-pstate only)
+    pub cpufreq_epp: Option<String>,
+    /// ACPI platform profile (e.g., "low-power", "balanced", "performance")
+    pub platform_profile: Option<String>,
+}
+
+impl PowerInfo {
+    /// Collect power supply, governor and platform profile state.
+    pub fn collect() -> Self {
+        let mut info = Self::default();
+        let mut capacities = Vec::new();
+        let mut power_total: Option<f64> = None;
+
+        if let Ok(entries) = std::fs::read_dir("/sys/class/power_supply") {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let supply_type = read_trimmed(&path.join("type")).unwrap_or_default();
+
+                match supply_type.as_str() {
+                    "Mains" | "USB" => {
+                        if let Some(online) = read_trimmed(&path.join("online")) {
+                            let online = online == "1";
+                            info.ac_online = Some(info.ac_online.unwrap_or(false) || online);
+                        }
+                    }
+                    "Battery" => {
+                        // Skip peripheral batteries (mice, keyboards) reported by HID
+                        if read_trimmed(&path.join("scope")).as_deref() == Some("Device") {
+                            continue;
+                        }
+
+                        if let Some(capacity) =
+                            read_trimmed(&path.join("capacity")).and_then(|s| s.parse().ok())
+                        {
+                            capacities.push(capacity);
+                        }
+                        if info.battery_status.is_none() {
+                            info.battery_status = read_trimmed(&path.join("status"));
+                        }
+                        if let Some(watts) = Self::read_battery_power(&path) {
+                            power_total = Some(power_total.unwrap_or(0.0) + watts);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if !capacities.is_empty() {
+            info.battery_capacity_percent =
+                Some(capacities.iter().sum::<f64>() / capacities.len() as f64);
+        }
+        info.battery_power_watts = power_total;
+        info.cpufreq_governor = read_trimmed(Path::new(
+            "/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor",
+        ));
+        info.cpufreq_epp = read_trimmed(Path::new(
+            "/sys/devices/system/cpu/cpu0/cpufreq/energy_performance_preference",
+        ));
+        info.platform_profile = read_trimmed(Path::new("/sys/firmware/acpi/platform_profile"));
+
+        info
+    }
+
+    /// Read the battery energy rate in watts.
+    ///
+    /// Most batteries expose `power_now` (µW); some only expose
+    /// `current_now` (µA) and `voltage_now` (µV).
+    fn read_battery_power(path: &Path) -> Option<f64> {
+        if let Some(power_uw) =
+            read_trimmed(&path.join("power_now")).and_then(|s| s.parse::<f64>().ok())
+        {
+            return Some(power_uw / 1_000_000.0);
+        }
+
+        let current_ua: f64 = read_trimmed(&path.join("current_now"))?.parse().ok()?;
+        let voltage_uv: f64 = read_trimmed(&path.join("voltage_now"))?.parse().ok()?;
+        Some(current_ua * voltage_uv / 1e12)
+    }
+
+    /// Check whether the machine is on battery or in a power-saving mode.
+    ///
+    /// Returns a short description of the reasons, or None if the system
+    /// appears to be running at full power.
+    ///
+    /// With `intel_pstate`/`amd-pstate` the "powersave" governor is the
+    /// normal dynamic default, so the energy/performance preference is used
+    /// instead when it is exposed.
+    pub fn low_power_reason(metrics: &Metrics) -> Option<String> {
+        let mut reasons = Vec::new();
+
+        if metrics.ac_online == Some(false)
+            || metrics.battery_status.as_deref() == Some("Discharging")
+        {
+            reasons.push("on battery".to_string());
+        }
+        match (
+            metrics.cpufreq_epp.as_deref(),
+            metrics.cpufreq_governor.as_deref(),
+        ) {
+            (Some(epp), _) if epp == "power" || epp == "balance_power" => {
+                reasons.push(format!("epp={}", epp));
+            }
+            (None, Some(gov)) if gov == "powersave" || gov == "conservative" => {
+                reasons.push(format!("governor={}", gov));
+            }
+            _ => {}
+        }
+        if let Some(profile) = metrics.platform_profile.as_deref() {
+            if profile == "low-power" || profile == "quiet" || profile == "cool" {
+                reasons.push(format!("profile={}", profile));
+            }
+        }
+
+        if reasons.is_empty() {
+            None
+        } else {
+            Some(reasons.join(", "))
+        }
+    }
+}
+
+/// Read a sysfs attribute and trim whitespace.
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
@@ -0,0 +1,1014 @@
+//! Prometheus text exposition for slow-rs.
+//!
+//! This module renders the latest [`Metrics`] sample in the Prometheus
+//! text format (version 0.0.4). It is either served over HTTP for scraping
+//! (`--prometheus-listen`) or written to a `.prom` file for node_exporter's
+//! textfile collector (`--textfile-dir`); both use the same metric names.
+//!
+//! # Metric Types
+//!
+//! - **Gauges**: instantaneous values (memory, load, PSI, temperatures,
+//!   benchmark results)
+//! - **Counters**: `/proc` counters (`/proc/stat`, `/proc/vmstat`,
+//!   `/proc/diskstats`, `/proc/net/dev`) are read when a sample is rendered
+//!   and exported as the kernel's totals since boot, like node_exporter
+//!   does, so they don't reset when slow-rs restarts. Only slow-rs's own
+//!   counters (benchmark runs and skips) count from when it started.
+//!
+//! Values are exported in base units: names and fields that carry a unit
+//! suffix elsewhere (`_mb`, `_ms`, `_us`, `_ns`, `_mb_per_sec`, ...) are
+//! renamed to `_bytes`, `_seconds` and `_bytes_per_second` and converted
+//! on output (see [`UNITS`]). CPU time is exported in seconds.
+//!
+//! Benchmarks may not run on every sample (see `--compute-interval`), so
+//! benchmark gauges keep reporting the result of their most recent run.
+//!
+//! All metric names carry the `slow_rs_` prefix. Per-item series use the
+//! labels `device` (disks, network interfaces), `sensor` (temperature
+//! sensors), `source` (where a temperature came from), plus `cpu`,
+//! `target` and `benchmark` where appropriate.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::collectors;
+use crate::metrics::{parse_labeled, Metrics};
+
+/// Prefix for all exported metric names.
+const PREFIX: &str = "slow_rs_";
+
+/// A gauge read directly from a [`Metrics`] field: name, help text, value.
+pub type GaugeField = (&'static str, &'static str, fn(&Metrics) -> Option<f64>);
+
+/// Benchmark result gauges (kept at their last value between runs).
+///
+/// Also the field list of the InfluxDB `bench` measurement.
+pub const BENCHMARK_GAUGES: &[GaugeField] = &[
+    (
+        "io_read_mb_per_sec",
+        "Sequential read throughput (MB/s)",
+        |m| m.io_read_mb_per_sec,
+    ),
+    (
+        "io_write_mb_per_sec",
+        "Sequential write throughput (MB/s)",
+        |m| m.io_write_mb_per_sec,
+    ),
+    (
+        "sha256_duration_ms",
+        "Read+SHA256 benchmark time (ms)",
+        |m| m.sha256_duration_ms,
+    ),
+    ("iops_read", "Random 4K read IOPS", |m| m.iops_read),
+    (
+        "iops_read_p50_us",
+        "Random 4K read p50 latency (µs)",
+        |m| m.iops_read_p50_us,
+    ),
+    (
+        "iops_read_p99_us",
+        "Random 4K read p99 latency (µs)",
+        |m| m.iops_read_p99_us,
+    ),
+    (
+        "iops_read_p999_us",
+        "Random 4K read p99.9 latency (µs)",
+        |m| m.iops_read_p999_us,
+    ),
+    ("iops_write", "Random 4K write IOPS", |m| m.iops_write),
+    (
+        "iops_write_p50_us",
+        "Random 4K write p50 latency (µs)",
+        |m| m.iops_write_p50_us,
+    ),
+    (
+        "iops_write_p99_us",
+        "Random 4K write p99 latency (µs)",
+        |m| m.iops_write_p99_us,
+    ),
+    (
+        "iops_write_p999_us",
+        "Random 4K write p99.9 latency (µs)",
+        |m| m.iops_write_p999_us,
+    ),
+    ("fsync_p50_ms", "fsync p50 latency (ms)", |m| m.fsync_p50_ms),
+    ("fsync_p99_ms", "fsync p99 latency (ms)", |m| m.fsync_p99_ms),
+    ("fsync_max_ms", "fsync max latency (ms)", |m| m.fsync_max_ms),
+    ("fdatasync_p50_ms", "fdatasync p50 latency (ms)", |m| {
+        m.fdatasync_p50_ms
+    }),
+    ("fdatasync_p99_ms", "fdatasync p99 latency (ms)", |m| {
+        m.fdatasync_p99_ms
+    }),
+    ("fdatasync_max_ms", "fdatasync max latency (ms)", |m| {
+        m.fdatasync_max_ms
+    }),
+    ("meta_create_ops_per_sec", "File creates per second", |m| {
+        m.meta_create_ops_per_sec
+    }),
+    ("meta_stat_ops_per_sec", "File stats per second", |m| {
+        m.meta_stat_ops_per_sec
+    }),
+    ("meta_rename_ops_per_sec", "File renames per second", |m| {
+        m.meta_rename_ops_per_sec
+    }),
+    ("meta_unlink_ops_per_sec", "File unlinks per second", |m| {
+        m.meta_unlink_ops_per_sec
+    }),
+    ("wakeup_min_us", "Minimum timer wakeup latency (µs)", |m| {
+        m.wakeup_min_us
+    }),
+    ("wakeup_avg_us", "Average timer wakeup latency (µs)", |m| {
+        m.wakeup_avg_us
+    }),
+    ("wakeup_max_us", "Maximum timer wakeup latency (µs)", |m| {
+        m.wakeup_max_us
+    }),
+    ("wakeup_p99_us", "p99 timer wakeup latency (µs)", |m| {
+        m.wakeup_p99_us
+    }),
+    (
+        "memory_alloc_duration_ms",
+        "64MB allocation benchmark time (ms)",
+        |m| m.memory_alloc_duration_ms,
+    ),
+    (
+        "compute_duration_ms",
+        "SHA256 compute benchmark time (ms)",
+        |m| m.compute_duration_ms,
+    ),
+    (
+        "core_compute_fastest_ms",
+        "Fastest single-core compute time (ms)",
+        |m| m.core_compute_fastest_ms,
+    ),
+    (
+        "core_compute_slowest_ms",
+        "Slowest single-core compute time (ms)",
+        |m| m.core_compute_slowest_ms,
+    ),
+    ("all_core_compute_ms", "All-core compute time (ms)", |m| {
+        m.all_core_compute_ms
+    }),
+    (
+        "compute_scaling_efficiency",
+        "All-core scaling efficiency (%)",
+        |m| m.compute_scaling_efficiency,
+    ),
+    ("mem_copy_gb_per_sec", "STREAM copy bandwidth (GB/s)", |m| {
+        m.mem_copy_gb_per_sec
+    }),
+    (
+        "mem_scale_gb_per_sec",
+        "STREAM scale bandwidth (GB/s)",
+        |m| m.mem_scale_gb_per_sec,
+    ),
+    ("mem_add_gb_per_sec", "STREAM add bandwidth (GB/s)", |m| {
+        m.mem_add_gb_per_sec
+    }),
+    (
+        "mem_triad_gb_per_sec",
+        "STREAM triad bandwidth (GB/s)",
+        |m| m.mem_triad_gb_per_sec,
+    ),
+    ("mem_latency_l1_ns", "L1 load-to-use latency (ns)", |m| {
+        m.mem_latency_l1_ns
+    }),
+    ("mem_latency_l2_ns", "L2 load-to-use latency (ns)", |m| {
+        m.mem_latency_l2_ns
+    }),
+    ("mem_latency_llc_ns", "LLC load-to-use latency (ns)", |m| {
+        m.mem_latency_llc_ns
+    }),
+    (
+        "mem_latency_dram_ns",
+        "DRAM load-to-use latency (ns)",
+        |m| m.mem_latency_dram_ns,
+    ),
+    (
+        "baseline_worst_pct",
+        "Worst benchmark as % of baseline",
+        |m| m.baseline_worst_pct,
+    ),
+];
+
+/// Labeled benchmark gauges from "label:value" list fields: name, label, help, list.
+pub type ListField = (
+    &'static str,
+    &'static str,
+    &'static str,
+    fn(&Metrics) -> Option<&String>,
+);
+
+/// Per-item benchmark results (kept at their last value between runs).
+pub const BENCHMARK_LISTS: &[ListField] = &[
+    (
+        "core_compute_ms",
+        "cpu",
+        "Single-core compute time per CPU (ms)",
+        |m| m.core_compute_ms.as_ref(),
+    ),
+    (
+        "target_read_mb_per_sec",
+        "target",
+        "Sequential read throughput per --io-target (MB/s)",
+        |m| m.target_read_mb_per_sec.as_ref(),
+    ),
+    (
+        "target_write_mb_per_sec",
+        "target",
+        "Sequential write throughput per --io-target (MB/s)",
+        |m| m.target_write_mb_per_sec.as_ref(),
+    ),
+    (
+        "target_iops_read",
+        "target",
+        "Random 4K read IOPS per --io-target",
+        |m| m.target_iops_read.as_ref(),
+    ),
+    (
+        "target_iops_read_p99_us",
+        "target",
+        "Random 4K read p99 latency per --io-target (µs)",
+        |m| m.target_iops_read_p99_us.as_ref(),
+    ),
+    (
+        "target_iops_write",
+        "target",
+        "Random 4K write IOPS per --io-target",
+        |m| m.target_iops_write.as_ref(),
+    ),
+    (
+        "target_fsync_p99_ms",
+        "target",
+        "fsync p99 latency per --io-target (ms)",
+        |m| m.target_fsync_p99_ms.as_ref(),
+    ),
+    (
+        "target_meta_create_ops_per_sec",
+        "target",
+        "File creates per second per --io-target",
+        |m| m.target_meta_create_ops_per_sec.as_ref(),
+    ),
+    (
+        "baseline_pct",
+        "benchmark",
+        "Benchmark performance as % of baseline",
+        |m| m.baseline_pct.as_ref(),
+    ),
+];
+
+/// System gauges.
+const SYSTEM_GAUGES: &[GaugeField] = &[
+    ("mem_total_mb", "Total memory (MB)", |m| {
+        Some(m.mem_total_mb as f64)
+    }),
+    ("mem_used_mb", "Used memory (MB)", |m| {
+        Some(m.mem_used_mb as f64)
+    }),
+    ("mem_free_mb", "Free memory (MB)", |m| {
+        Some(m.mem_free_mb as f64)
+    }),
+    ("mem_available_mb", "Available memory (MB)", |m| {
+        Some(m.mem_available_mb as f64)
+    }),
+    ("swap_total_mb", "Total swap (MB)", |m| {
+        Some(m.swap_total_mb as f64)
+    }),
+    ("swap_used_mb", "Used swap (MB)", |m| {
+        Some(m.swap_used_mb as f64)
+    }),
+    ("mem_buffers_mb", "Buffer memory (MB)", |m| {
+        Some(m.mem_buffers_mb as f64)
+    }),
+    ("mem_cached_mb", "Page cache (MB)", |m| {
+        Some(m.mem_cached_mb as f64)
+    }),
+    ("dirty_mb", "Dirty pages waiting for writeback (MB)", |m| {
+        Some(m.dirty_mb as f64)
+    }),
+    ("writeback_mb", "Pages under writeback (MB)", |m| {
+        Some(m.writeback_mb as f64)
+    }),
+    ("anon_pages_mb", "Anonymous pages (MB)", |m| {
+        Some(m.anon_pages_mb as f64)
+    }),
+    ("mapped_mb", "Mapped files (MB)", |m| {
+        Some(m.mapped_mb as f64)
+    }),
+    ("shmem_mb", "Shared memory (MB)", |m| {
+        Some(m.shmem_mb as f64)
+    }),
+    ("slab_mb", "Kernel slab (MB)", |m| Some(m.slab_mb as f64)),
+    ("page_tables_mb", "Page tables (MB)", |m| {
+        Some(m.page_tables_mb as f64)
+    }),
+    ("cpu_usage_percent", "CPU usage (%)", |m| {
+        Some(m.cpu_usage_percent as f64)
+    }),
+    ("cpu_count", "Number of CPUs", |m| Some(m.cpu_count as f64)),
+    ("load_avg_1", "1-minute load average", |m| {
+        Some(m.load_avg_1)
+    }),
+    ("load_avg_5", "5-minute load average", |m| {
+        Some(m.load_avg_5)
+    }),
+    ("load_avg_15", "15-minute load average", |m| {
+        Some(m.load_avg_15)
+    }),
+    ("process_count", "Number of processes", |m| {
+        Some(m.process_count as f64)
+    }),
+    ("procs_running", "Runnable tasks", |m| {
+        Some(m.procs_running as f64)
+    }),
+    ("procs_blocked", "Tasks blocked on I/O", |m| {
+        Some(m.procs_blocked as f64)
+    }),
+    ("fd_allocated", "Allocated file descriptors", |m| {
+        Some(m.fd_allocated as f64)
+    }),
+    ("fd_max", "Maximum file descriptors", |m| {
+        Some(m.fd_max as f64)
+    }),
+    ("uptime_seconds", "System uptime (seconds)", |m| {
+        Some(m.uptime_secs)
+    }),
+    ("ac_online", "AC adapter online (1/0)", |m| {
+        m.ac_online.map(bool_value)
+    }),
+    ("battery_capacity_percent", "Battery charge (%)", |m| {
+        m.battery_capacity_percent
+    }),
+    (
+        "battery_power_watts",
+        "Battery charge/discharge rate (W)",
+        |m| m.battery_power_watts,
+    ),
+    (
+        "smart_health_all_passed",
+        "All disks passed SMART health check (1/0)",
+        |m| m.smart_health_all_passed.map(bool_value),
+    ),
+    (
+        "smart_reallocated_sectors_total",
+        "Reallocated sectors across all disks",
+        |m| m.smart_reallocated_sectors_total.map(|v| v as f64),
+    ),
+    (
+        "smart_pending_sectors_total",
+        "Pending sectors across all disks",
+        |m| m.smart_pending_sectors_total.map(|v| v as f64),
+    ),
+];
+
+/// Bytes in a MB as used by slow-rs (memory and throughput are in MiB).
+const MB: f64 = 1024.0 * 1024.0;
+
+/// Unit suffixes converted to base units on export: suffix, unit in the
+/// help text, base-unit suffix, base unit in the help text, scale.
+///
+/// Longer suffixes come first, since the first match wins.
+const UNITS: &[(&str, &str, &str, &str, f64)] = &[
+    (
+        "_mb_per_sec",
+        "(MB/s)",
+        "_bytes_per_second",
+        "(bytes/s)",
+        MB,
+    ),
+    (
+        "_gb_per_sec",
+        "(GB/s)",
+        "_bytes_per_second",
+        "(bytes/s)",
+        1e9,
+    ),
+    ("_ms_total", "(ms)", "_seconds_total", "(seconds)", 1e-3),
+    ("_mb", "(MB)", "_bytes", "(bytes)", MB),
+    ("_ms", "(ms)", "_seconds", "(seconds)", 1e-3),
+    ("_us", "(µs)", "_seconds", "(seconds)", 1e-6),
+    ("_ns", "(ns)", "_seconds", "(seconds)", 1e-9),
+];
+
+/// Counters from `/proc/stat` and `/proc/vmstat`: name, help, total.
+type CounterField = (&'static str, &'static str, fn(&ProcTotals) -> u64);
+
+/// Kernel totals read for the system-wide counters.
+#[derive(Default)]
+struct ProcTotals {
+    /// CPU time, context switches and interrupts from `/proc/stat`
+    cpu: collectors::CpuStats,
+    /// Paging and swapping from `/proc/vmstat`
+    vm: collectors::VmStats,
+}
+
+/// `/proc` counters without a device dimension.
+const PROC_COUNTERS: &[CounterField] = &[
+    ("context_switches_total", "Context switches", |p| {
+        p.cpu.context_switches
+    }),
+    ("interrupts_total", "Interrupts serviced", |p| {
+        p.cpu.interrupts
+    }),
+    ("pgfault_total", "Page faults", |p| p.vm.pgfault),
+    ("pgmajfault_total", "Major page faults (disk reads)", |p| {
+        p.vm.pgmajfault
+    }),
+    ("pgpgin_total", "Pages paged in", |p| p.vm.pgpgin),
+    ("pgpgout_total", "Pages paged out", |p| p.vm.pgpgout),
+    ("pswpin_total", "Pages swapped in", |p| p.vm.pswpin),
+    ("pswpout_total", "Pages swapped out", |p| p.vm.pswpout),
+];
+
+/// A single exposition sample: labels and value.
+struct Sample {
+    labels: Vec<(&'static str, String)>,
+    value: f64,
+}
+
+impl Sample {
+    /// Sample without labels.
+    fn plain(value: f64) -> Self {
+        Self {
+            labels: Vec::new(),
+            value,
+        }
+    }
+
+    /// Sample with a single label.
+    fn labeled(label: &'static str, label_value: &str, value: f64) -> Self {
+        Self {
+            labels: vec![(label, label_value.to_string())],
+            value,
+        }
+    }
+}
+
+/// Renders [`Metrics`] samples in the Prometheus text format.
+///
+/// Keeps the state needed between samples: benchmark run/skip counts and
+/// the last result of each benchmark.
+#[derive(Default)]
+pub struct PrometheusRenderer {
+    /// Benchmark run/skip counts, keyed by metric name and benchmark
+    totals: BTreeMap<(&'static str, String), f64>,
+    /// Last samples of each benchmark family, reused when it didn't run
+    last_benchmarks: BTreeMap<&'static str, Vec<Sample>>,
+}
+
+impl PrometheusRenderer {
+    /// Render a sample. Must be called once per collected sample, since
+    /// benchmark run/skip counters accumulate the sample's runs.
+    pub fn render(&mut self, m: &Metrics) -> String {
+        let mut out = String::new();
+
+        write_family(
+            &mut out,
+            "last_sample_timestamp_seconds",
+            "gauge",
+            "Unix time of the latest sample",
+            &[Sample::plain(m.timestamp as f64)],
+        );
+
+        // === Benchmarks (sticky gauges) ===
+        for (name, help, value_fn) in BENCHMARK_GAUGES {
+            let samples = value_fn(m).map(Sample::plain).into_iter().collect();
+            self.write_benchmark(&mut out, name, help, samples);
+        }
+        for (name, label, help, list_fn) in BENCHMARK_LISTS {
+            let samples = list_fn(m)
+                .map(|list| {
+                    parse_labeled(list)
+                        .into_iter()
+                        .map(|(item, value)| Sample::labeled(label, item, value))
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.write_benchmark(&mut out, name, help, samples);
+        }
+        let runs = self.accumulate_list("benchmark_runs_total", m.benchmarks_ran.as_deref());
+        write_family(
+            &mut out,
+            "benchmark_runs_total",
+            "counter",
+            "Benchmark runs",
+            &runs,
+        );
+        let skips = self.accumulate_list("benchmark_skips_total", m.benchmarks_skipped.as_deref());
+        write_family(
+            &mut out,
+            "benchmark_skips_total",
+            "counter",
+            "Benchmark runs skipped by --adaptive",
+            &skips,
+        );
+
+        // === System gauges ===
+        for (name, help, value_fn) in SYSTEM_GAUGES {
+            if let Some(value) = value_fn(m) {
+                write_family(&mut out, name, "gauge", help, &[Sample::plain(value)]);
+            }
+        }
+        self.write_pressure(&mut out, m);
+        self.write_temperatures(&mut out, m);
+        if m.cpufreq_governor.is_some()
+            || m.platform_profile.is_some()
+            || m.battery_status.is_some()
+        {
+            let labels = [
+                ("governor", &m.cpufreq_governor),
+                ("epp", &m.cpufreq_epp),
+                ("profile", &m.platform_profile),
+                ("battery_status", &m.battery_status),
+            ]
+            .into_iter()
+            .filter_map(|(label, value)| Some((label, value.clone()?)))
+            .collect();
+            write_family(
+                &mut out,
+                "power_info",
+                "gauge",
+                "Power management state (always 1)",
+                &[Sample { labels, value: 1.0 }],
+            );
+        }
+
+        // === Counters (kernel totals) ===
+        write_proc_counters(&mut out);
+        write_disk_devices(&mut out);
+        write_net_devices(&mut out);
+
+        out
+    }
+
+    /// Write a benchmark family, falling back to its last results.
+    fn write_benchmark(
+        &mut self,
+        out: &mut String,
+        name: &'static str,
+        help: &str,
+        samples: Vec<Sample>,
+    ) {
+        if !samples.is_empty() {
+            self.last_benchmarks.insert(name, samples);
+        }
+        if let Some(samples) = self.last_benchmarks.get(name) {
+            write_family(out, name, "gauge", help, samples);
+        }
+    }
+
+    /// Count each item of a comma-separated list once and return all totals.
+    fn accumulate_list(&mut self, name: &'static str, list: Option<&str>) -> Vec<Sample> {
+        for item in list
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.is_empty())
+        {
+            *self.totals.entry((name, item.to_string())).or_default() += 1.0;
+        }
+        self.totals
+            .range((name, String::new())..)
+            .take_while(|((n, _), _)| *n == name)
+            .map(|((_, item), total)| Sample::labeled("benchmark", item, *total))
+            .collect()
+    }
+
+    /// Write PSI averages as one labeled family.
+    fn write_pressure(&self, out: &mut String, m: &Metrics) {
+        let psi = [
+            ("cpu", "some", "10", m.cpu_pressure_some_avg10),
+            ("cpu", "some", "60", m.cpu_pressure_some_avg60),
+            ("cpu", "some", "300", m.cpu_pressure_some_avg300),
+            ("memory", "some", "10", m.mem_pressure_some_avg10),
+            ("memory", "some", "60", m.mem_pressure_some_avg60),
+            ("memory", "full", "10", m.mem_pressure_full_avg10),
+            ("io", "some", "10", m.io_pressure_some_avg10),
+            ("io", "some", "60", m.io_pressure_some_avg60),
+            ("io", "full", "10", m.io_pressure_full_avg10),
+            ("io", "full", "60", m.io_pressure_full_avg60),
+        ];
+        let samples: Vec<Sample> = psi
+            .into_iter()
+            .filter_map(|(resource, kind, window, value)| {
+                Some(Sample {
+                    labels: vec![
+                        ("resource", resource.to_string()),
+                        ("kind", kind.to_string()),
+                        ("window", window.to_string()),
+                    ],
+                    value: value?,
+                })
+            })
+            .collect();
+        write_family(
+            out,
+            "pressure_percent",
+            "gauge",
+            "Pressure stall information: share of time tasks were stalled (%)",
+            &samples,
+        );
+    }
+
+    /// Write temperature gauges with sensor/device and source labels.
+    fn write_temperatures(&self, out: &mut String, m: &Metrics) {
+        let with_source = |mut sample: Sample, source: &Option<String>| {
+            if let Some(source) = source {
+                sample.labels.push(("source", source.clone()));
+            }
+            sample
+        };
+
+        let mut cpu = Vec::new();
+        if let Some(temp) = m.cpu_temp_celsius {
+            cpu.push(with_source(
+                Sample::labeled("sensor", "cpu", temp),
+                &m.cpu_temp_source,
+            ));
+        }
+        if let Some(temp) = m.max_temp_celsius {
+            cpu.push(Sample::labeled("sensor", "max", temp));
+        }
+        write_family(
+            out,
+            "temperature_celsius",
+            "gauge",
+            "CPU and hottest sensor temperature (°C)",
+            &cpu,
+        );
+
+        let dimms: Vec<Sample> = m
+            .dimm_temps
+            .as_deref()
+            .map(parse_labeled)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(sensor, temp)| {
+                with_source(Sample::labeled("sensor", sensor, temp), &m.dimm_temp_source)
+            })
+            .collect();
+        write_family(
+            out,
+            "dimm_temperature_celsius",
+            "gauge",
+            "Memory module temperature (°C)",
+            &dimms,
+        );
+
+        let disks: Vec<Sample> = m
+            .disk_temps
+            .as_deref()
+            .map(parse_labeled)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(device, temp)| {
+                with_source(Sample::labeled("device", device, temp), &m.disk_temp_source)
+            })
+            .collect();
+        write_family(
+            out,
+            "disk_temperature_celsius",
+            "gauge",
+            "Disk temperature (°C)",
+            &disks,
+        );
+        if let Some(temp) = m.disk_temp_max {
+            write_family(
+                out,
+                "disk_temperature_max_celsius",
+                "gauge",
+                "Hottest disk temperature (°C)",
+                &[with_source(Sample::plain(temp), &m.disk_temp_source)],
+            );
+        }
+
+        let ipmi: Vec<Sample> = m
+            .ipmi_temps
+            .iter()
+            .map(|t| Sample {
+                labels: vec![
+                    ("sensor", t.name.clone()),
+                    ("status", t.status.clone()),
+                    ("source", "ipmi".to_string()),
+                ],
+                value: t.temp_celsius,
+            })
+            .collect();
+        write_family(
+            out,
+            "ipmi_temperature_celsius",
+            "gauge",
+            "IPMI/BMC temperature sensor (°C)",
+            &ipmi,
+        );
+    }
+}
+
+/// Write CPU time and the other system-wide counters from `/proc/stat` and
+/// `/proc/vmstat`.
+fn write_proc_counters(out: &mut String) {
+    let totals = ProcTotals {
+        cpu: collectors::read_cpu_stats().unwrap_or_default(),
+        vm: collectors::read_vmstat().unwrap_or_default(),
+    };
+
+    // SAFETY: sysconf has no preconditions
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    let ticks = if ticks > 0 { ticks as f64 } else { 100.0 };
+    let cpu = &totals.cpu;
+    let cpu_samples: Vec<Sample> = [
+        ("user", cpu.user),
+        ("nice", cpu.nice),
+        ("system", cpu.system),
+        ("idle", cpu.idle),
+        ("iowait", cpu.iowait),
+        ("irq", cpu.irq),
+        ("softirq", cpu.softirq),
+        ("steal", cpu.steal),
+    ]
+    .into_iter()
+    .map(|(mode, jiffies)| Sample::labeled("mode", mode, jiffies as f64 / ticks))
+    .collect();
+    write_family(
+        out,
+        "cpu_seconds_total",
+        "counter",
+        "CPU time by mode (seconds)",
+        &cpu_samples,
+    );
+
+    for (name, help, value_fn) in PROC_COUNTERS {
+        let total = value_fn(&totals) as f64;
+        write_family(out, name, "counter", help, &[Sample::plain(total)]);
+    }
+}
+
+/// Write per-device disk counters from `/proc/diskstats`.
+fn write_disk_devices(out: &mut String) {
+    let devices = collectors::read_disk_stats_by_device().unwrap_or_default();
+    type DiskField = (
+        &'static str,
+        &'static str,
+        fn(&collectors::DiskStats) -> u64,
+    );
+    let fields: [DiskField; 10] = [
+        ("disk_reads_completed_total", "Reads completed", |d| {
+            d.reads_completed
+        }),
+        ("disk_reads_merged_total", "Reads merged", |d| {
+            d.reads_merged
+        }),
+        ("disk_read_bytes_total", "Bytes read", |d| {
+            d.sectors_read * 512
+        }),
+        ("disk_read_time_ms_total", "Time spent reading (ms)", |d| {
+            d.read_time_ms
+        }),
+        ("disk_writes_completed_total", "Writes completed", |d| {
+            d.writes_completed
+        }),
+        ("disk_writes_merged_total", "Writes merged", |d| {
+            d.writes_merged
+        }),
+        ("disk_written_bytes_total", "Bytes written", |d| {
+            d.sectors_written * 512
+        }),
+        ("disk_write_time_ms_total", "Time spent writing (ms)", |d| {
+            d.write_time_ms
+        }),
+        ("disk_io_time_ms_total", "Time spent doing I/O (ms)", |d| {
+            d.io_time_ms
+        }),
+        (
+            "disk_weighted_io_time_ms_total",
+            "Weighted time spent doing I/O (ms)",
+            |d| d.weighted_io_time_ms,
+        ),
+    ];
+
+    for (name, help, value_fn) in fields {
+        let samples: Vec<Sample> = devices
+            .iter()
+            .map(|(device, stats)| Sample::labeled("device", device, value_fn(stats) as f64))
+            .collect();
+        write_family(out, name, "counter", help, &samples);
+    }
+
+    let in_progress: Vec<Sample> = devices
+        .iter()
+        .map(|(device, stats)| Sample::labeled("device", device, stats.io_in_progress as f64))
+        .collect();
+    write_family(
+        out,
+        "disk_io_in_progress",
+        "gauge",
+        "I/O operations currently in progress",
+        &in_progress,
+    );
+}
+
+/// Write per-interface network counters from `/proc/net/dev`.
+fn write_net_devices(out: &mut String) {
+    let interfaces = collectors::read_net_stats_by_interface().unwrap_or_default();
+    type NetField = (&'static str, &'static str, fn(&collectors::NetStats) -> u64);
+    let fields: [NetField; 6] = [
+        ("net_rx_bytes_total", "Bytes received", |n| n.rx_bytes),
+        ("net_tx_bytes_total", "Bytes transmitted", |n| n.tx_bytes),
+        ("net_rx_packets_total", "Packets received", |n| n.rx_packets),
+        ("net_tx_packets_total", "Packets transmitted", |n| {
+            n.tx_packets
+        }),
+        ("net_rx_errors_total", "Receive errors", |n| n.rx_errors),
+        ("net_tx_errors_total", "Transmit errors", |n| n.tx_errors),
+    ];
+
+    for (name, help, value_fn) in fields {
+        let samples: Vec<Sample> = interfaces
+            .iter()
+            .map(|(iface, stats)| Sample::labeled("device", iface, value_fn(stats) as f64))
+            .collect();
+        write_family(out, name, "counter", help, &samples);
+    }
+}
+
+/// Write one metric family (HELP, TYPE and samples). Empty families are omitted.
+///
+/// Names with a unit suffix from [`UNITS`] are converted to base units.
+fn write_family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[Sample]) {
+    let samples: Vec<&Sample> = samples.iter().filter(|s| s.value.is_finite()).collect();
+    if samples.is_empty() {
+        return;
+    }
+
+    let (name, help, scale) = match UNITS
+        .iter()
+        .find_map(|unit| Some((name.strip_suffix(unit.0)?, unit)))
+    {
+        Some((stem, (_, unit, base, base_unit, scale))) => (
+            format!("{}{}", stem, base),
+            help.replace(unit, base_unit),
+            *scale,
+        ),
+        None => (name.to_string(), help.to_string(), 1.0),
+    };
+
+    let _ = writeln!(out, "# HELP {}{} {}", PREFIX, name, help);
+    let _ = writeln!(out, "# TYPE {}{} {}", PREFIX, name, kind);
+    for sample in samples {
+        let _ = write!(out, "{}{}", PREFIX, name);
+        if !sample.labels.is_empty() {
+            let labels: Vec<String> = sample
+                .labels
+                .iter()
+                .map(|(label, value)| format!("{}=\"{}\"", label, escape_label(value)))
+                .collect();
+            let _ = write!(out, "{{{}}}", labels.join(","));
+        }
+        let _ = writeln!(out, " {}", sample.value * scale);
+    }
+}
+
+/// Escape a label value (backslash, double quote and newline).
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Convert a boolean to a 1/0 gauge value.
+fn bool_value(value: bool) -> f64 {
+    if value {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// HTTP exporter serving the latest rendered sample on `/metrics`.
+pub struct PrometheusExporter {
+    /// Latest exposition text, shared with the HTTP thread
+    latest: Arc<Mutex<String>>,
+}
+
+impl PrometheusExporter {
+    /// Bind the listen address and start serving in a background thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound (e.g. port in use).
+    pub fn start(listen: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(listen)?;
+        let latest = Arc::new(Mutex::new(String::new()));
+
+        let shared = latest.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle_request(stream, &shared);
+            }
+        });
+
+        Ok(Self { latest })
+    }
+
+    /// Make a rendered sample available to scrapers.
+    pub fn publish(&self, text: &str) {
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = text.to_string();
+        }
+    }
+}
+
+/// Writes the latest rendered sample for node_exporter's textfile collector.
+pub struct TextfileWriter {
+    /// Final `.prom` file read by node_exporter
+    path: PathBuf,
+    /// Temp file in the same directory (not matched by `*.prom`)
+    tmp_path: PathBuf,
+}
+
+impl TextfileWriter {
+    /// Name of the file written to the textfile directory.
+    const FILE_NAME: &'static str = "slow_rs.prom";
+
+    /// Prepare to write into a textfile collector directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `dir` is not an existing directory.
+    pub fn new(dir: &str) -> std::io::Result<Self> {
+        let dir = Path::new(dir);
+        if !dir.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("textfile directory {} does not exist", dir.display()),
+            ));
+        }
+
+        Ok(Self {
+            path: dir.join(Self::FILE_NAME),
+            tmp_path: dir.join(format!(".{}.{}.tmp", Self::FILE_NAME, std::process::id())),
+        })
+    }
+
+    /// Write a rendered sample atomically (write to a temp file, then rename),
+    /// so node_exporter never reads a partially written file.
+    pub fn write(&self, text: &str) -> std::io::Result<()> {
+        std::fs::write(&self.tmp_path, text)?;
+        std::fs::rename(&self.tmp_path, &self.path)
+    }
+}
+
+/// Answer a single HTTP request.
+fn handle_request(mut stream: TcpStream, latest: &Mutex<String>) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+
+    // Read the request head (we only need the request line)
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < 8192 {
+        let n = stream.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
+        request.extend_from_slice(&buffer[..n]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path) {
+        ("GET", "/metrics") => (
+            "200 OK",
+            "text/plain; version=0.0.4; charset=utf-8",
+            latest.lock().map(|s| s.clone()).unwrap_or_default(),
+        ),
+        ("GET", "/") => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            "<html><body><a href=\"/metrics\">/metrics</a></body></html>\n".to_string(),
+        ),
+        _ => (
+            "404 Not Found",
+            "text/plain; charset=utf-8",
+            "not found\n".to_string(),
+        ),
+    };
+
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
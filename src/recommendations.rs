@@ -3,7 +3,11 @@
 //! This module analyzes metrics and generates actionable advice
 //! when issues are detected.
 
+use std::collections::VecDeque;
+
+use crate::anomaly::detect_anomalies;
 use crate::metrics::Metrics;
+use crate::power::PowerInfo;
 use crate::thresholds::{Severity, Thresholds};
 
 /// A recommendation with severity and actionable advice.
@@ -11,6 +15,9 @@ use crate::thresholds::{Severity, Thresholds};
 pub struct Recommendation {
     /// Severity level of the issue
     pub severity: Severity,
+    /// Stable identifier of the problem, shared by its warning and critical
+    /// variants (and by the anomaly check for the same metric)
+    pub kind: &'static str,
     /// Short title for the issue
     pub title: String,
     /// Actionable advice for resolving the issue
@@ -18,7 +25,15 @@ pub struct Recommendation {
 }
 
 /// Generate recommendations based on current metrics.
-pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> Vec<Recommendation> {
+///
+/// `history` holds the samples leading up to (and usually including)
+/// `metrics`, and is used for trend-based checks such as rising
+/// benchmark times.
+pub fn generate_recommendations(
+    metrics: &Metrics,
+    history: &VecDeque<Metrics>,
+    thresholds: &Thresholds,
+) -> Vec<Recommendation> {
     let mut recs = Vec::new();
 
     // I/O pressure
@@ -27,12 +42,14 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "io_pressure",
                 title: "High I/O Pressure".into(),
                 advice: "Check: iotop, iostat -x 1, dmesg for disk errors".into(),
             });
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "io_pressure",
                 title: "Elevated I/O Pressure".into(),
                 advice: "Monitor: iotop -o to identify I/O-heavy processes".into(),
             });
@@ -45,12 +62,14 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "mem_pressure",
                 title: "High Memory Pressure".into(),
                 advice: "Check: ps aux --sort=-%mem | head, consider adding RAM".into(),
             });
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "mem_pressure",
                 title: "Memory Pressure Detected".into(),
                 advice: "Monitor: free -h, check for memory-hungry processes".into(),
             });
@@ -61,6 +80,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     if metrics.pswpin > 0 || metrics.pswpout > 0 {
         recs.push(Recommendation {
             severity: Severity::Warning,
+            kind: "swap",
             title: "Swap Activity".into(),
             advice: format!(
                 "Swapping in:{} out:{}. Check: ps aux --sort=-%mem",
@@ -74,6 +94,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     if mem_severity == Severity::Critical {
         recs.push(Recommendation {
             severity: Severity::Critical,
+            kind: "mem_available",
             title: "Critically Low Memory".into(),
             advice: format!(
                 "Only {} MB available. Kill processes or add RAM immediately",
@@ -83,6 +104,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     } else if mem_severity == Severity::Warning {
         recs.push(Recommendation {
             severity: Severity::Warning,
+            kind: "mem_available",
             title: "Low Available Memory".into(),
             advice: format!(
                 "{} MB available. Monitor memory usage closely",
@@ -97,6 +119,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "cpu_temp",
                 title: "CPU Overheating".into(),
                 advice: format!(
                     "CPU at {:.0}C. Check cooling, clean dust, verify thermal paste",
@@ -106,6 +129,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "cpu_temp",
                 title: "CPU Running Hot".into(),
                 advice: format!("CPU at {:.0}C. Consider improving cooling", temp),
             });
@@ -118,6 +142,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "dimm_temp",
                 title: "RAM Overheating".into(),
                 advice: format!(
                     "DIMM at {:.0}C. Check case airflow, consider RAM cooling",
@@ -127,6 +152,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "dimm_temp",
                 title: "RAM Running Warm".into(),
                 advice: format!("DIMM at {:.0}C. Ensure adequate airflow", temp),
             });
@@ -139,12 +165,14 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "disk_temp",
                 title: "Disk Overheating".into(),
                 advice: format!("Disk at {:.0}C. Check cooling, may cause data loss", temp),
             });
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "disk_temp",
                 title: "Disk Running Hot".into(),
                 advice: format!("Disk at {:.0}C. Consider better cooling", temp),
             });
@@ -159,6 +187,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         if severity == Severity::Critical {
             recs.push(Recommendation {
                 severity,
+                kind: "iowait",
                 title: "Severe I/O Wait".into(),
                 advice: format!(
                     "{:.0}% CPU waiting for I/O. Disk is severe bottleneck",
@@ -168,6 +197,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         } else if severity == Severity::Warning {
             recs.push(Recommendation {
                 severity,
+                kind: "iowait",
                 title: "High I/O Wait".into(),
                 advice: format!(
                     "{:.0}% CPU waiting for I/O. Disk may be bottleneck",
@@ -182,6 +212,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     if cpu_severity == Severity::Critical {
         recs.push(Recommendation {
             severity: Severity::Critical,
+            kind: "cpu_usage",
             title: "CPU Saturated".into(),
             advice: format!(
                 "CPU at {:.0}%. Check: top, htop for CPU-intensive processes",
@@ -194,6 +225,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     if metrics.pgmajfault > 100 {
         recs.push(Recommendation {
             severity: Severity::Warning,
+            kind: "major_faults",
             title: "High Major Faults".into(),
             advice: format!(
                 "{} major faults. System may be thrashing. Add RAM or reduce load",
@@ -206,6 +238,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
     if metrics.dirty_mb > 1024 {
         recs.push(Recommendation {
             severity: Severity::Warning,
+            kind: "dirty_pages",
             title: "High Dirty Pages".into(),
             advice: format!(
                 "{} MB waiting to be written. I/O may be backed up",
@@ -224,6 +257,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
             "nr" => {
                 recs.push(Recommendation {
                     severity: Severity::Critical,
+                    kind: "ipmi_dimm",
                     title: "DIMM NON-RECOVERABLE".into(),
                     advice: format!("{}. Check BMC logs: sudo ipmitool sel list", details),
                 });
@@ -231,6 +265,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
             "cr" => {
                 recs.push(Recommendation {
                     severity: Severity::Critical,
+                    kind: "ipmi_dimm",
                     title: "DIMM CRITICAL".into(),
                     advice: format!("{}. Check cooling immediately", details),
                 });
@@ -238,6 +273,7 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
             "nc" => {
                 recs.push(Recommendation {
                     severity: Severity::Warning,
+                    kind: "ipmi_dimm",
                     title: "DIMM Warning".into(),
                     advice: format!("{}. Monitor closely", details),
                 });
@@ -246,6 +282,181 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
         }
     }
 
+    // Benchmarks may run less often than metrics are sampled (see
+    // --compute-interval/--io-interval), so judge each by its latest run.
+
+    // Random I/O latency tails (what makes a desktop feel slow)
+    let io = latest_run(metrics, history, |m| {
+        m.iops_read_p99_us.is_some() || m.iops_write_p99_us.is_some()
+    });
+    let latencies = [
+        ("iops_read_p99", "Read", io.iops_read_p99_us),
+        ("iops_write_p99", "Write", io.iops_write_p99_us),
+    ];
+    for (kind, op, p99_us) in latencies {
+        let Some(p99_us) = p99_us else {
+            continue;
+        };
+        let p99_ms = p99_us / 1000.0;
+        let severity = thresholds.io_latency_severity(p99_ms);
+        if severity == Severity::Critical {
+            recs.push(Recommendation {
+                severity,
+                kind,
+                title: format!("Severe Disk {} Latency", op),
+                advice: format!(
+                    "4K {} p99 latency {:.0} ms. Check: iostat -x 1 (await), SMART, dmesg for resets",
+                    op.to_lowercase(),
+                    p99_ms
+                ),
+            });
+        } else if severity == Severity::Warning {
+            recs.push(Recommendation {
+                severity,
+                kind,
+                title: format!("High Disk {} Latency", op),
+                advice: format!(
+                    "4K {} p99 latency {:.0} ms. Check: iotop -o for competing I/O",
+                    op.to_lowercase(),
+                    p99_ms
+                ),
+            });
+        }
+    }
+
+    // fsync stalls (databases, git, package managers block on these)
+    let sync = latest_run(metrics, history, |m| m.fsync_p99_ms.is_some());
+    if let Some(p99_ms) = sync.fsync_p99_ms {
+        let severity = thresholds.io_latency_severity(p99_ms);
+        if severity != Severity::Normal {
+            recs.push(Recommendation {
+                severity,
+                kind: "fsync_p99",
+                title: "Slow fsync".into(),
+                advice: format!(
+                    "fsync p99 {:.0} ms (max {:.0} ms). Check: Dirty/Writeback in /proc/meminfo, SSD firmware",
+                    p99_ms,
+                    sync.fsync_max_ms.unwrap_or(p99_ms)
+                ),
+            });
+        }
+    }
+
+    // Scheduler wakeup latency (stutter)
+    let wakeup = latest_run(metrics, history, |m| m.wakeup_p99_us.is_some());
+    if let Some(p99_us) = wakeup.wakeup_p99_us {
+        let severity = thresholds.wakeup_latency_severity(p99_us);
+        if severity == Severity::Critical {
+            recs.push(Recommendation {
+                severity,
+                kind: "wakeup_p99",
+                title: "Severe Scheduling Latency".into(),
+                advice: format!(
+                    "Wakeups up to {:.1} ms late (p99). Check: top for CPU hogs, /proc/interrupts for IRQ storms",
+                    p99_us / 1000.0
+                ),
+            });
+        } else if severity == Severity::Warning {
+            recs.push(Recommendation {
+                severity,
+                kind: "wakeup_p99",
+                title: "Scheduling Latency".into(),
+                advice: format!(
+                    "Wakeups {:.1} ms late (p99). Interactive apps may stutter",
+                    p99_us / 1000.0
+                ),
+            });
+        }
+    }
+
+    // Individual cores much slower than their siblings
+    let scaling = latest_run(metrics, history, |m| m.core_compute_ms.is_some());
+    if let Some(ref cores) = scaling.slow_cores {
+        recs.push(Recommendation {
+            severity: Severity::Warning,
+            kind: "slow_cores",
+            title: "Slow CPU Cores".into(),
+            advice: format!(
+                "CPU {} slower than siblings (worst {:.1} ms vs best {:.1} ms). Check: per-core MHz, throttling, steal",
+                cores,
+                scaling.core_compute_slowest_ms.unwrap_or(0.0),
+                scaling.core_compute_fastest_ms.unwrap_or(0.0)
+            ),
+        });
+    }
+
+    // Poor multi-core scaling
+    if let Some(efficiency) = scaling.compute_scaling_efficiency {
+        if efficiency < thresholds.scaling_efficiency_warning {
+            recs.push(Recommendation {
+                severity: Severity::Warning,
+                kind: "scaling",
+                title: "Poor Multi-Core Scaling".into(),
+                advice: format!(
+                    "All-core run at {:.0}% efficiency. Check: power/thermal limits, VM CPU overcommit",
+                    efficiency
+                ),
+            });
+        }
+    }
+
+    // Benchmarks regressed versus the recorded baseline
+    let compared = latest_run(metrics, history, |m| m.baseline_pct.is_some());
+    if let Some(ref regressions) = compared.baseline_regressions {
+        let worst = compared.baseline_worst_pct.unwrap_or(100.0);
+        let factor = thresholds.baseline_regression_factor;
+        let severity = if worst < 100.0 / (factor * factor) {
+            Severity::Critical
+        } else {
+            Severity::Warning
+        };
+        recs.push(Recommendation {
+            severity,
+            kind: "baseline",
+            title: "Benchmark Regression".into(),
+            advice: format!(
+                "Below baseline (% of baseline): {}. Compare with the known-good run",
+                regressions.replace(',', ", ")
+            ),
+        });
+    }
+
+    // Low-power mode combined with slowing benchmarks
+    if let Some(reason) = PowerInfo::low_power_reason(metrics) {
+        if let Some(ratio) = compute_slowdown_ratio(history) {
+            if ratio >= thresholds.benchmark_slowdown_warning {
+                recs.push(Recommendation {
+                    severity: Severity::Warning,
+                    kind: "power_saving",
+                    title: "Power Saving Slowdown".into(),
+                    advice: format!(
+                        "Benchmarks {:.0}% slower while {}. Plug in AC or switch to a performance profile",
+                        (ratio - 1.0) * 100.0,
+                        reason
+                    ),
+                });
+            }
+        }
+    }
+
+    // Metrics far outside their own history, unless a static threshold
+    // already reports them
+    for anomaly in detect_anomalies(metrics, history, thresholds) {
+        if anomaly.threshold_severity != Severity::Normal {
+            continue;
+        }
+        let check = anomaly.check;
+        recs.push(Recommendation {
+            severity: anomaly.severity,
+            kind: check.key,
+            title: format!("Unusual {}", check.label),
+            advice: format!(
+                "{:.1}{} vs. typical {:.1}{} ({:.1} robust z). Check: {}",
+                anomaly.value, check.unit, anomaly.median, check.unit, anomaly.z, check.hint
+            ),
+        });
+    }
+
     // Sort by severity (critical first)
     recs.sort_by_key(|r| match r.severity {
         Severity::Critical => 0,
@@ -255,3 +466,43 @@ pub fn generate_recommendations(metrics: &Metrics, thresholds: &Thresholds) -> V
 
     recs
 }
+
+/// Most recent sample in which a benchmark ran, starting with `metrics`.
+///
+/// Falls back to `metrics` if the benchmark hasn't run within `history`.
+pub fn latest_run<'a>(
+    metrics: &'a Metrics,
+    history: &'a VecDeque<Metrics>,
+    ran: fn(&Metrics) -> bool,
+) -> &'a Metrics {
+    if ran(metrics) {
+        return metrics;
+    }
+    history.iter().rev().find(|m| ran(m)).unwrap_or(metrics)
+}
+
+/// Ratio of recent compute benchmark times to earlier ones.
+///
+/// Compares the mean of the last 3 runs against the median of the
+/// older runs. Returns None until at least 6 runs are available.
+pub fn compute_slowdown_ratio(history: &VecDeque<Metrics>) -> Option<f64> {
+    let times: Vec<f64> = history
+        .iter()
+        .filter_map(|m| m.compute_duration_ms)
+        .collect();
+    if times.len() < 6 {
+        return None;
+    }
+    let (older, recent) = times.split_at(times.len() - 3);
+
+    let mut older = older.to_vec();
+    older.sort_by(|a, b| a.total_cmp(b));
+    let reference = older[older.len() / 2];
+    let recent_avg = recent.iter().sum::<f64>() / recent.len() as f64;
+
+    if reference > 0.0 {
+        Some(recent_avg / reference)
+    } else {
+        None
+    }
+}
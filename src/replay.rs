@@ -0,0 +1,296 @@
+//! Replay of recorded samples for slow-rs.
+//!
+//! `slow-rs replay <file>` loads a recording written with `--csv-file` or
+//! `--jsonl-file` (optionally compressed by log rotation) back into
+//! [`Metrics`] and plays it through the regular dashboard. Recommendations
+//! are regenerated for every sample with the current thresholds, so a
+//! recording from an older version benefits from newer checks.
+//!
+//! # Controls
+//!
+//! - `Space`: Play/pause
+//! - `Left`/`Right`: Step one sample
+//! - `PgUp`/`PgDn`: Seek 10% of the recording
+//! - `Home`/`End`: Jump to the start/end
+//! - `+`/`-`: Double/halve the playback speed
+//! - `z`: Toggle between the last `--history-size` samples and the full
+//!   range from the start of the recording
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::time::Instant;
+
+use crate::metrics::Metrics;
+
+/// Longest gap between two samples (seconds of recording time), so periods
+/// where monitoring was stopped don't stall playback or count as samples.
+const MAX_GAP_SECS: f64 = 60.0;
+
+/// Slowest and fastest playback speed.
+const SPEED_RANGE: (f64, f64) = (0.25, 1024.0);
+
+/// Load all samples from a CSV or JSON Lines recording.
+///
+/// The format is detected from the content; gzip and zstd compressed files
+/// (as written by `--rotate-compress`) are decompressed transparently.
+pub fn load(path: &str) -> std::io::Result<Vec<Metrics>> {
+    let mut reader = open_decompressed(path)?;
+
+    let is_json = reader
+        .fill_buf()?
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|&b| b == b'{');
+
+    let samples = if is_json {
+        read_jsonl(reader)?
+    } else {
+        read_csv(reader, path)?
+    };
+
+    if samples.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("{} contains no samples", path),
+        ));
+    }
+    Ok(samples)
+}
+
+/// Open a file, decompressing it if it starts with a gzip or zstd header.
+fn open_decompressed(path: &str) -> std::io::Result<Box<dyn BufRead>> {
+    let mut magic = [0u8; 4];
+    let len = File::open(path)?.read(&mut magic)?;
+    let file = File::open(path)?;
+
+    Ok(match &magic[..len] {
+        [0x1f, 0x8b, ..] => Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(file))),
+        [0x28, 0xb5, 0x2f, 0xfd] => Box::new(BufReader::new(zstd::Decoder::new(file)?)),
+        _ => Box::new(BufReader::new(file)),
+    })
+}
+
+/// Read a CSV recording; columns are matched by name, so any schema version loads.
+///
+/// Logs written before the schema was versioned can have rows with more or
+/// fewer fields than their header. Fields beyond the header are ignored and
+/// missing trailing fields take their default. Rows that still can't be
+/// read are skipped with a warning, unless no row can be read at all.
+fn read_csv(reader: impl Read, path: &str) -> std::io::Result<Vec<Metrics>> {
+    let mut samples = Vec::new();
+    let mut skipped = 0;
+    let mut first_error = None;
+    for result in csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(reader)
+        .deserialize()
+    {
+        match result {
+            Ok(sample) => samples.push(sample),
+            Err(e) => {
+                skipped += 1;
+                first_error.get_or_insert_with(|| csv_error(path, &e));
+            }
+        }
+    }
+
+    if let Some(error) = first_error {
+        if samples.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, error));
+        }
+        eprintln!(
+            "Warning: skipped {} unreadable row(s), first at {}",
+            skipped, error
+        );
+    }
+    Ok(samples)
+}
+
+/// Describe a CSV error as "file:line: problem".
+fn csv_error(path: &str, e: &csv::Error) -> String {
+    let line = e
+        .position()
+        .map(|pos| format!("{}:{}", path, pos.line()))
+        .unwrap_or_else(|| path.to_string());
+    match e.kind() {
+        csv::ErrorKind::UnequalLengths {
+            expected_len, len, ..
+        } => format!("{}: expected {} fields, got {}", line, expected_len, len),
+        csv::ErrorKind::Deserialize { err, .. } => format!("{}: {}", line, err),
+        _ => format!("{}: {}", line, e),
+    }
+}
+
+/// Read a JSON Lines recording (blank lines are skipped).
+fn read_jsonl(reader: impl BufRead) -> std::io::Result<Vec<Metrics>> {
+    let mut samples = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let sample = serde_json::from_str(&line).map_err(|e| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("line {}: {}", index + 1, e),
+            )
+        })?;
+        samples.push(sample);
+    }
+    Ok(samples)
+}
+
+/// Recording time (seconds) between two consecutive samples.
+///
+/// Capped at [`MAX_GAP_SECS`]; missing or out-of-order timestamps count as
+/// one second.
+pub fn sample_gap(current: &Metrics, next: &Metrics) -> f64 {
+    let gap = next.timestamp - current.timestamp;
+    if gap > 0 {
+        (gap as f64).min(MAX_GAP_SECS)
+    } else {
+        1.0
+    }
+}
+
+/// Playback position and settings for a loaded recording.
+pub struct Playback {
+    /// All samples of the recording
+    samples: Vec<Metrics>,
+    /// Index of the sample currently shown
+    pub position: usize,
+    /// Whether playback is paused
+    pub paused: bool,
+    /// Playback speed (1 = real time)
+    pub speed: f64,
+    /// Show the full range from the start instead of the last `history_size` samples
+    pub zoomed: bool,
+    /// Number of samples shown when not zoomed
+    history_size: usize,
+    /// Samples shown in the dashboard (ending at `position`)
+    window: VecDeque<Metrics>,
+    /// Recording time (seconds) not yet consumed by stepping
+    budget: f64,
+    /// When [`Playback::tick`] last ran
+    last_tick: Instant,
+}
+
+impl Playback {
+    /// Start playback at the first sample.
+    pub fn new(samples: Vec<Metrics>, speed: f64, history_size: usize) -> Self {
+        let mut playback = Self {
+            samples,
+            position: 0,
+            paused: false,
+            speed: speed.clamp(SPEED_RANGE.0, SPEED_RANGE.1),
+            zoomed: false,
+            history_size: history_size.max(1),
+            window: VecDeque::new(),
+            budget: 0.0,
+            last_tick: Instant::now(),
+        };
+        playback.refresh();
+        playback
+    }
+
+    /// Number of samples in the recording.
+    pub fn sample_count(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Samples to draw, ending at the current position.
+    pub fn window(&self) -> &VecDeque<Metrics> {
+        &self.window
+    }
+
+    /// Advance by however much recording time has elapsed at the current speed.
+    pub fn tick(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_tick).as_secs_f64();
+        self.last_tick = now;
+        if self.paused {
+            return;
+        }
+
+        self.budget += elapsed * self.speed;
+        let start = self.position;
+        while self.position + 1 < self.samples.len() {
+            let gap = self.gap_after(self.position);
+            if self.budget < gap {
+                break;
+            }
+            self.budget -= gap;
+            self.position += 1;
+        }
+
+        if self.position + 1 == self.samples.len() {
+            self.paused = true;
+            self.budget = 0.0;
+        }
+        if self.position != start {
+            self.refresh();
+        }
+    }
+
+    /// Toggle play/pause (playing from the end restarts at the beginning).
+    pub fn toggle_pause(&mut self) {
+        if self.paused && self.position + 1 == self.samples.len() {
+            self.seek_to(0);
+        }
+        self.paused = !self.paused;
+        self.budget = 0.0;
+    }
+
+    /// Move by `delta` samples.
+    pub fn seek_by(&mut self, delta: isize) {
+        self.seek_to(self.position.saturating_add_signed(delta));
+    }
+
+    /// Move by a fraction of the recording (negative = backwards).
+    pub fn seek_fraction(&mut self, fraction: f64) {
+        let delta = (self.samples.len() as f64 * fraction).round() as isize;
+        self.seek_by(if delta == 0 {
+            fraction.signum() as isize
+        } else {
+            delta
+        });
+    }
+
+    /// Jump to a sample (clamped to the recording).
+    pub fn seek_to(&mut self, position: usize) {
+        let position = position.min(self.samples.len() - 1);
+        self.budget = 0.0;
+        if position != self.position {
+            self.position = position;
+            self.refresh();
+        }
+    }
+
+    /// Multiply the speed by `factor`, within [`SPEED_RANGE`].
+    pub fn change_speed(&mut self, factor: f64) {
+        self.speed = (self.speed * factor).clamp(SPEED_RANGE.0, SPEED_RANGE.1);
+    }
+
+    /// Switch between the recent window and the full range.
+    pub fn toggle_zoom(&mut self) {
+        self.zoomed = !self.zoomed;
+        self.refresh();
+    }
+
+    /// Recording time between a sample and the next one.
+    fn gap_after(&self, position: usize) -> f64 {
+        sample_gap(&self.samples[position], &self.samples[position + 1])
+    }
+
+    /// Rebuild the drawn window after the position or zoom changed.
+    fn refresh(&mut self) {
+        let end = self.position + 1;
+        let start = if self.zoomed {
+            0
+        } else {
+            end.saturating_sub(self.history_size)
+        };
+        self.window = self.samples[start..end].iter().cloned().collect();
+    }
+}
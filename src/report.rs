@@ -0,0 +1,910 @@
+//! Offline reports for slow-rs.
+//!
+//! `slow-rs report <file>` turns a recording (anything [`crate::replay`]
+//! can load) into a single summary document:
+//!
+//! - min/avg/p95/max of the key metrics
+//! - time spent in Warning and Critical for each threshold
+//! - the top recommendations, regenerated for every sample, with when
+//!   they fired
+//! - SVG charts of the dashboard series, with the same threshold lines
+//!
+//! HTML reports are self-contained (charts are inline SVG). Markdown
+//! reports link to SVG files written to `<report>-charts/` next to the
+//! report, so Markdown charts need `--output`.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, ReportArgs, ReportFormat};
+use crate::metrics::{format_duration, format_value, Metrics};
+use crate::recommendations::generate_recommendations;
+use crate::replay;
+use crate::thresholds::{Severity, Thresholds};
+
+/// A summarised metric: name, unit and value extractor.
+pub type Series = (&'static str, &'static str, fn(&Metrics) -> Option<f64>);
+
+/// Metrics summarised in the report (rows without data are left out).
+pub const SERIES: &[Series] = &[
+    ("CPU usage", "%", |m| Some(m.cpu_usage_percent as f64)),
+    ("Load average (1 min)", "", |m| Some(m.load_avg_1)),
+    ("Memory available", "MB", |m| {
+        Some(m.mem_available_mb as f64)
+    }),
+    ("Swap used", "MB", |m| Some(m.swap_used_mb as f64)),
+    ("I/O wait", "%", Metrics::iowait_percent),
+    ("CPU pressure (some, 10s)", "%", |m| {
+        m.cpu_pressure_some_avg10
+    }),
+    ("Memory pressure (some, 10s)", "%", |m| {
+        m.mem_pressure_some_avg10
+    }),
+    ("I/O pressure (some, 10s)", "%", |m| {
+        m.io_pressure_some_avg10
+    }),
+    ("CPU temperature", "°C", |m| m.cpu_temp_celsius),
+    ("DIMM temperature (max)", "°C", |m| m.dimm_temp_max),
+    ("Disk temperature (max)", "°C", |m| m.disk_temp_max),
+    ("Sequential read", "MB/s", |m| {
+        m.unreduced(m.io_read_mb_per_sec)
+    }),
+    ("Sequential write", "MB/s", |m| {
+        m.unreduced(m.io_write_mb_per_sec)
+    }),
+    ("Random read", "IOPS", |m| m.unreduced(m.iops_read)),
+    ("Random read p99", "ms", |m| {
+        m.unreduced(m.iops_read_p99_us).map(|us| us / 1000.0)
+    }),
+    ("Random write", "IOPS", |m| m.unreduced(m.iops_write)),
+    ("fsync p99", "ms", |m| m.unreduced(m.fsync_p99_ms)),
+    ("fdatasync p99", "ms", |m| m.unreduced(m.fdatasync_p99_ms)),
+    ("File creates", "ops/s", |m| {
+        m.unreduced(m.meta_create_ops_per_sec)
+    }),
+    ("Compute (SHA256)", "ms", |m| m.compute_duration_ms),
+    ("Memory alloc (64MB)", "ms", |m| m.memory_alloc_duration_ms),
+    ("Multi-core scaling", "%", |m| m.compute_scaling_efficiency),
+    ("STREAM triad", "GB/s", |m| {
+        m.unreduced(m.mem_triad_gb_per_sec)
+    }),
+    ("DRAM latency", "ns", |m| m.mem_latency_dram_ns),
+    ("Wakeup p99", "µs", |m| m.wakeup_p99_us),
+    ("Baseline (worst)", "%", |m| m.baseline_worst_pct),
+];
+
+/// A threshold check: name, value extractor and severity function.
+type SeverityCheck = (
+    &'static str,
+    fn(&Metrics) -> Option<f64>,
+    fn(&Thresholds, f64) -> Severity,
+);
+
+/// Thresholds whose Warning/Critical time is reported.
+///
+/// Benchmarks that don't run every sample keep their last result until
+/// the next run.
+const SEVERITY_CHECKS: &[SeverityCheck] = &[
+    (
+        "CPU usage",
+        |m| Some(m.cpu_usage_percent as f64),
+        |t, v| t.cpu_usage_severity(v as f32),
+    ),
+    (
+        "Memory available",
+        |m| Some(m.mem_available_mb as f64),
+        |t, v| t.memory_available_severity(v as u64),
+    ),
+    (
+        "I/O pressure",
+        |m| m.io_pressure_some_avg10,
+        Thresholds::io_pressure_severity,
+    ),
+    (
+        "Memory pressure",
+        |m| m.mem_pressure_some_avg10,
+        Thresholds::mem_pressure_severity,
+    ),
+    (
+        "I/O wait",
+        Metrics::iowait_percent,
+        Thresholds::iowait_severity,
+    ),
+    (
+        "CPU temperature",
+        |m| m.cpu_temp_celsius,
+        Thresholds::cpu_temp_severity,
+    ),
+    (
+        "DIMM temperature",
+        |m| m.dimm_temp_max,
+        Thresholds::dimm_temp_severity,
+    ),
+    (
+        "Disk temperature",
+        |m| m.disk_temp_max,
+        Thresholds::disk_temp_severity,
+    ),
+    (
+        "Random read p99 latency",
+        |m| m.iops_read_p99_us.map(|us| us / 1000.0),
+        Thresholds::io_latency_severity,
+    ),
+    (
+        "Wakeup p99 latency",
+        |m| m.wakeup_p99_us,
+        Thresholds::wakeup_latency_severity,
+    ),
+];
+
+/// A chart of one dashboard series.
+struct ChartSpec {
+    /// Title as shown in the TUI
+    title: &'static str,
+    /// Value extractor (samples without a value are skipped)
+    value_fn: fn(&Metrics) -> Option<f64>,
+    /// Line colour
+    color: &'static str,
+    /// Warning threshold line
+    warning: Option<f64>,
+    /// Critical threshold line
+    critical: Option<f64>,
+}
+
+/// Charts from the system and benchmark pages of the TUI.
+fn chart_specs(t: &Thresholds) -> Vec<ChartSpec> {
+    let chart = |title, value_fn, color| ChartSpec {
+        title,
+        value_fn,
+        color,
+        warning: None,
+        critical: None,
+    };
+    let baseline_warning = 100.0 / t.baseline_regression_factor;
+
+    vec![
+        ChartSpec {
+            warning: Some(t.cpu_usage_warning as f64),
+            critical: Some(t.cpu_usage_critical as f64),
+            ..chart("CPU %", |m| Some(m.cpu_usage_percent as f64), "#b58900")
+        },
+        ChartSpec {
+            warning: Some(t.memory_available_warning_mb as f64),
+            critical: Some(t.memory_available_critical_mb as f64),
+            ..chart(
+                "Mem Avail MB",
+                |m| Some(m.mem_available_mb as f64),
+                "#2e8b57",
+            )
+        },
+        ChartSpec {
+            warning: Some(t.io_pressure_warning),
+            critical: Some(t.io_pressure_critical),
+            ..chart(
+                "I/O Pressure % [PSI]",
+                |m| m.io_pressure_some_avg10,
+                "#c71585",
+            )
+        },
+        chart("I/O Read MB/s [bench]", |m| m.io_read_mb_per_sec, "#008b8b"),
+        chart(
+            "I/O Write MB/s [bench]",
+            |m| m.io_write_mb_per_sec,
+            "#20b2aa",
+        ),
+        ChartSpec {
+            warning: Some(t.cpu_temp_warning),
+            critical: Some(t.cpu_temp_critical),
+            ..chart("CPU °C", |m| m.cpu_temp_celsius, "#daa520")
+        },
+        ChartSpec {
+            warning: Some(t.dimm_temp_warning),
+            critical: Some(t.dimm_temp_critical),
+            ..chart("RAM °C", |m| m.dimm_temp_max, "#b22222")
+        },
+        ChartSpec {
+            warning: Some(t.disk_temp_warning),
+            critical: Some(t.disk_temp_critical),
+            ..chart("Disk °C", |m| m.disk_temp_max, "#cd5c5c")
+        },
+        ChartSpec {
+            warning: Some(baseline_warning),
+            critical: Some(baseline_warning / t.baseline_regression_factor),
+            ..chart("Baseline % [worst]", |m| m.baseline_worst_pct, "#555555")
+        },
+        chart("Compute ms [SHA256]", |m| m.compute_duration_ms, "#b58900"),
+        chart("Alloc ms [64MB]", |m| m.memory_alloc_duration_ms, "#2e8b57"),
+        chart(
+            "Scaling % [all cores]",
+            |m| m.compute_scaling_efficiency,
+            "#daa520",
+        ),
+        ChartSpec {
+            warning: Some(t.wakeup_latency_warning_us),
+            critical: Some(t.wakeup_latency_critical_us),
+            ..chart("Wakeup p99 µs [timer]", |m| m.wakeup_p99_us, "#4682b4")
+        },
+        chart("Triad GB/s [STREAM]", |m| m.mem_triad_gb_per_sec, "#008b8b"),
+        chart(
+            "DRAM ns [pointer chase]",
+            |m| m.mem_latency_dram_ns,
+            "#c71585",
+        ),
+        chart("Rand Read IOPS [4K]", |m| m.iops_read, "#20b2aa"),
+        ChartSpec {
+            warning: Some(t.io_latency_p99_warning_ms),
+            critical: Some(t.io_latency_p99_critical_ms),
+            ..chart(
+                "Rand Read p99 ms [4K]",
+                |m| m.iops_read_p99_us.map(|us| us / 1000.0),
+                "#cd5c5c",
+            )
+        },
+        chart(
+            "Create ops/s [metadata]",
+            |m| m.meta_create_ops_per_sec,
+            "#3cb371",
+        ),
+        ChartSpec {
+            warning: Some(t.io_latency_p99_warning_ms),
+            critical: Some(t.io_latency_p99_critical_ms),
+            ..chart("fsync p99 ms", |m| m.fsync_p99_ms, "#b22222")
+        },
+        ChartSpec {
+            warning: Some(t.io_latency_p99_warning_ms),
+            critical: Some(t.io_latency_p99_critical_ms),
+            ..chart("fdatasync p99 ms", |m| m.fdatasync_p99_ms, "#ba55d3")
+        },
+    ]
+}
+
+/// Distribution of one metric over the recording.
+struct Stats {
+    /// Smallest value
+    min: f64,
+    /// Mean value
+    avg: f64,
+    /// 95th percentile (nearest rank)
+    p95: f64,
+    /// Largest value
+    max: f64,
+    /// Number of samples with a value
+    count: usize,
+}
+
+impl Stats {
+    /// Summarise values (None if there are none).
+    fn from_values(mut values: Vec<f64>) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        let rank = (0.95 * values.len() as f64).ceil() as usize;
+        Some(Self {
+            min: values[0],
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            p95: values[rank.clamp(1, values.len()) - 1],
+            max: values[values.len() - 1],
+            count: values.len(),
+        })
+    }
+}
+
+/// Time a threshold spent above Warning and Critical.
+struct SeverityTime {
+    /// Check name
+    name: &'static str,
+    /// Seconds at Warning
+    warning_secs: f64,
+    /// Seconds at Critical
+    critical_secs: f64,
+}
+
+/// A recommendation over the whole recording.
+struct Firing {
+    /// Recommendation title
+    title: String,
+    /// Worst severity reached
+    severity: Severity,
+    /// Advice from the worst (latest on ties) occurrence
+    advice: String,
+    /// Total seconds the recommendation was active
+    active_secs: f64,
+    /// Start and end timestamps of each period it was active
+    episodes: Vec<(i64, i64)>,
+}
+
+/// Everything a report contains, independent of the output format.
+struct Summary {
+    /// Recording file name
+    file: String,
+    /// First and last timestamp
+    span: (i64, i64),
+    /// Number of samples
+    sample_count: usize,
+    /// Total recorded time in seconds (gaps capped)
+    recorded_secs: f64,
+    /// Per-metric statistics (only metrics with data)
+    stats: Vec<(&'static Series, Stats)>,
+    /// Warning/Critical time per threshold (only thresholds that were crossed)
+    severity_times: Vec<SeverityTime>,
+    /// Recommendations, most severe and longest-lasting first
+    firings: Vec<Firing>,
+    /// Chart titles and SVG documents
+    charts: Vec<(&'static str, String)>,
+}
+
+/// Generate a report for `slow-rs report`.
+pub fn run(args: &ReportArgs, config: &Config) -> std::io::Result<()> {
+    let samples = replay::load(&args.file)?;
+    let thresholds = Thresholds::from_config(config);
+    let mut summary = summarize(&args.file, &samples, &thresholds, config.history_size);
+    summary.firings.truncate(args.top);
+
+    let format = args.format.unwrap_or_else(|| {
+        let is_html = args.output.as_deref().is_some_and(|path| {
+            let path = path.to_ascii_lowercase();
+            path.ends_with(".html") || path.ends_with(".htm")
+        });
+        if is_html {
+            ReportFormat::Html
+        } else {
+            ReportFormat::Markdown
+        }
+    });
+
+    let document = match format {
+        ReportFormat::Html => render_html(&summary),
+        ReportFormat::Markdown => {
+            let chart_dir = args.output.as_deref().map(chart_dir);
+            if let Some(dir) = &chart_dir {
+                write_chart_files(dir, &summary.charts)?;
+            }
+            render_markdown(&summary, chart_dir.as_deref())
+        }
+    };
+
+    match &args.output {
+        Some(path) => std::fs::write(path, document),
+        None => {
+            print!("{}", document);
+            Ok(())
+        }
+    }
+}
+
+/// Compute all report sections from the samples.
+fn summarize(
+    file: &str,
+    samples: &[Metrics],
+    thresholds: &Thresholds,
+    history_size: usize,
+) -> Summary {
+    // Each sample stands for the time until the next one; the last for the gap before it
+    let mut durations: Vec<f64> = samples
+        .windows(2)
+        .map(|pair| replay::sample_gap(&pair[0], &pair[1]))
+        .collect();
+    durations.push(durations.last().copied().unwrap_or(0.0));
+
+    let stats = SERIES
+        .iter()
+        .filter_map(|series| {
+            let values = samples.iter().filter_map(series.2).collect();
+            Some((series, Stats::from_values(values)?))
+        })
+        .collect();
+
+    let severity_times = SEVERITY_CHECKS
+        .iter()
+        .map(|&(name, value_fn, severity_fn)| {
+            let mut time = SeverityTime {
+                name,
+                warning_secs: 0.0,
+                critical_secs: 0.0,
+            };
+            let mut last = None;
+            for (m, secs) in samples.iter().zip(&durations) {
+                last = value_fn(m).or(last);
+                match last.map(|v| severity_fn(thresholds, v)) {
+                    Some(Severity::Warning) => time.warning_secs += secs,
+                    Some(Severity::Critical) => time.critical_secs += secs,
+                    _ => {}
+                }
+            }
+            time
+        })
+        .filter(|t| t.warning_secs > 0.0 || t.critical_secs > 0.0)
+        .collect();
+
+    let charts = chart_specs(thresholds)
+        .iter()
+        .filter_map(|spec| Some((spec.title, svg_chart(spec, samples)?)))
+        .collect();
+
+    Summary {
+        file: file.to_string(),
+        span: (
+            samples.first().map_or(0, |m| m.timestamp),
+            samples.last().map_or(0, |m| m.timestamp),
+        ),
+        sample_count: samples.len(),
+        recorded_secs: durations.iter().sum(),
+        stats,
+        severity_times,
+        firings: firings(samples, &durations, thresholds, history_size),
+        charts,
+    }
+}
+
+/// Regenerate recommendations for every sample and group them by title.
+fn firings(
+    samples: &[Metrics],
+    durations: &[f64],
+    thresholds: &Thresholds,
+    history_size: usize,
+) -> Vec<Firing> {
+    let history_size = history_size.max(1);
+    let mut history: VecDeque<Metrics> = VecDeque::with_capacity(history_size);
+    let mut firings: BTreeMap<String, Firing> = BTreeMap::new();
+    let mut active: BTreeSet<String> = BTreeSet::new();
+
+    for (m, &secs) in samples.iter().zip(durations) {
+        if history.len() >= history_size {
+            history.pop_front();
+        }
+        history.push_back(m.clone());
+
+        let end = m.timestamp + secs.round() as i64;
+        let mut now_active = BTreeSet::new();
+        for rec in generate_recommendations(m, &history, thresholds) {
+            let firing = firings.entry(rec.title.clone()).or_insert_with(|| Firing {
+                title: rec.title.clone(),
+                severity: rec.severity,
+                advice: rec.advice.clone(),
+                active_secs: 0.0,
+                episodes: Vec::new(),
+            });
+            if rec.severity >= firing.severity {
+                firing.severity = rec.severity;
+                firing.advice = rec.advice;
+            }
+            firing.active_secs += secs;
+            match firing.episodes.last_mut() {
+                Some(episode) if active.contains(&rec.title) => episode.1 = end,
+                _ => firing.episodes.push((m.timestamp, end)),
+            }
+            now_active.insert(rec.title);
+        }
+        active = now_active;
+    }
+
+    let mut firings: Vec<Firing> = firings.into_values().collect();
+    firings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then(b.active_secs.total_cmp(&a.active_secs))
+    });
+    firings
+}
+
+/// Chart size and plot margins in SVG user units.
+const SVG_WIDTH: f64 = 720.0;
+const SVG_HEIGHT: f64 = 200.0;
+const MARGIN_LEFT: f64 = 56.0;
+const MARGIN_RIGHT: f64 = 12.0;
+const MARGIN_TOP: f64 = 26.0;
+const MARGIN_BOTTOM: f64 = 24.0;
+
+/// Most points drawn per chart; longer series keep each bucket's min and max.
+const MAX_CHART_POINTS: usize = 1200;
+
+/// Render a chart as a standalone SVG document (None if the series has no data).
+///
+/// Uses the TUI's scaling rules: threshold lines are only drawn when the
+/// data comes within 50% of them.
+fn svg_chart(spec: &ChartSpec, samples: &[Metrics]) -> Option<String> {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .enumerate()
+        .filter_map(|(i, m)| {
+            let value = (spec.value_fn)(m)?;
+            // Fall back to the sample index if the recording has no timestamps
+            let x = if samples[0].timestamp > 0 {
+                m.timestamp as f64
+            } else {
+                i as f64
+            };
+            Some((x, value))
+        })
+        .collect();
+    if points.is_empty() {
+        return None;
+    }
+    let points = downsample(points);
+
+    let x_min = points[0].0;
+    let x_max = points[points.len() - 1].0.max(x_min + 1.0);
+    let min_y = points.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max_y = points.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+
+    let warning = spec.warning.filter(|&w| max_y >= w * 0.5);
+    let critical = spec.critical.filter(|&c| max_y >= c * 0.5);
+    let mut range_max = max_y;
+    for line in [warning, critical].into_iter().flatten() {
+        range_max = range_max.max(line * 1.1);
+    }
+    let (y_min, y_max) = if (range_max - min_y).abs() < 0.001 {
+        (min_y - 1.0, range_max + 1.0)
+    } else {
+        (min_y * 0.95, range_max * 1.05)
+    };
+
+    let plot_width = SVG_WIDTH - MARGIN_LEFT - MARGIN_RIGHT;
+    let plot_height = SVG_HEIGHT - MARGIN_TOP - MARGIN_BOTTOM;
+    let sx = |x: f64| MARGIN_LEFT + (x - x_min) / (x_max - x_min) * plot_width;
+    let sy = |y: f64| MARGIN_TOP + (y_max - y) / (y_max - y_min) * plot_height;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="11">"##,
+        w = SVG_WIDTH,
+        h = SVG_HEIGHT
+    );
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{:.1}" y="{:.1}" width="{:.1}" height="{:.1}" fill="none" stroke="#ccc"/>"##,
+        MARGIN_LEFT, MARGIN_TOP, plot_width, plot_height
+    );
+    let _ = writeln!(
+        svg,
+        r##"<text x="{:.1}" y="16" font-size="13" font-weight="bold">{}</text>"##,
+        MARGIN_LEFT,
+        escape_html(spec.title)
+    );
+
+    // Threshold lines with their values as y labels
+    for (line, color, label) in [(warning, "#e6a700", "W"), (critical, "#d00000", "C")] {
+        if let Some(value) = line {
+            let y = sy(value);
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{:.1}" y1="{y:.1}" x2="{:.1}" y2="{y:.1}" stroke="{color}" stroke-dasharray="4 3"/>"##,
+                MARGIN_LEFT,
+                MARGIN_LEFT + plot_width,
+            );
+            let _ = writeln!(
+                svg,
+                r##"<text x="{:.1}" y="{:.1}" text-anchor="end" fill="{color}">{label}:{:.0}</text>"##,
+                MARGIN_LEFT - 4.0,
+                y + 4.0,
+                value
+            );
+        }
+    }
+
+    // Axis labels: y range and the recording's start and end
+    for (value, y) in [(y_min, MARGIN_TOP + plot_height), (y_max, MARGIN_TOP + 8.0)] {
+        let _ = writeln!(
+            svg,
+            r##"<text x="{:.1}" y="{:.1}" text-anchor="end" fill="#666">{}</text>"##,
+            MARGIN_LEFT - 4.0,
+            y,
+            format_value(value)
+        );
+    }
+    if samples[0].timestamp > 0 {
+        let label_y = SVG_HEIGHT - 8.0;
+        let _ = writeln!(
+            svg,
+            r##"<text x="{:.1}" y="{label_y:.1}" fill="#666">{}</text>"##,
+            MARGIN_LEFT,
+            format_time(x_min as i64)
+        );
+        let _ = writeln!(
+            svg,
+            r##"<text x="{:.1}" y="{label_y:.1}" text-anchor="end" fill="#666">{}</text>"##,
+            MARGIN_LEFT + plot_width,
+            format_time(x_max as i64)
+        );
+    }
+
+    let path: Vec<String> = points
+        .iter()
+        .map(|&(x, y)| format!("{:.1},{:.1}", sx(x), sy(y)))
+        .collect();
+    let _ = writeln!(
+        svg,
+        r##"<polyline fill="none" stroke="{}" stroke-width="1.5" points="{}"/>"##,
+        spec.color,
+        path.join(" ")
+    );
+    svg.push_str("</svg>\n");
+    Some(svg)
+}
+
+/// Reduce a series to at most [`MAX_CHART_POINTS`], keeping spikes.
+fn downsample(points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    if points.len() <= MAX_CHART_POINTS {
+        return points;
+    }
+    let buckets = MAX_CHART_POINTS / 2;
+    let bucket_size = (points.len() + buckets - 1) / buckets;
+    points
+        .chunks(bucket_size)
+        .flat_map(|bucket| {
+            let min = bucket.iter().min_by(|a, b| a.1.total_cmp(&b.1)).copied();
+            let max = bucket.iter().max_by(|a, b| a.1.total_cmp(&b.1)).copied();
+            let (Some(min), Some(max)) = (min, max) else {
+                return Vec::new();
+            };
+            // Keep the two points in time order
+            if min.0 <= max.0 {
+                vec![min, max]
+            } else {
+                vec![max, min]
+            }
+        })
+        .collect()
+}
+
+/// Directory for the SVG files of a Markdown report (`report.md` → `report-charts`).
+fn chart_dir(output: &str) -> PathBuf {
+    let path = Path::new(output);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "report".to_string());
+    path.with_file_name(format!("{}-charts", stem))
+}
+
+/// File name of a chart's SVG (e.g. "io-pressure-psi.svg").
+fn chart_file_name(title: &str) -> String {
+    let mut slug = String::new();
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    format!("{}.svg", slug.trim_end_matches('-'))
+}
+
+/// Write the SVG files of a Markdown report.
+fn write_chart_files(dir: &Path, charts: &[(&str, String)]) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    for (title, svg) in charts {
+        std::fs::write(dir.join(chart_file_name(title)), svg)?;
+    }
+    Ok(())
+}
+
+/// Render the report as Markdown, linking charts in `chart_dir` if given.
+fn render_markdown(summary: &Summary, chart_dir: Option<&Path>) -> String {
+    let mut md = String::new();
+    let _ = writeln!(md, "# slow-rs report: {}\n", summary.file);
+    let _ = writeln!(
+        md,
+        "{} samples from {} to {} UTC ({} recorded).\n",
+        summary.sample_count,
+        format_time(summary.span.0),
+        format_time(summary.span.1),
+        format_duration(summary.recorded_secs.round() as i64)
+    );
+
+    md.push_str("## Metrics\n\n");
+    md.push_str("| Metric | Unit | Min | Avg | p95 | Max | Samples |\n");
+    md.push_str("|--------|------|----:|----:|----:|----:|--------:|\n");
+    for ((name, unit, _), stats) in &summary.stats {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            name,
+            unit,
+            format_value(stats.min),
+            format_value(stats.avg),
+            format_value(stats.p95),
+            format_value(stats.max),
+            stats.count
+        );
+    }
+
+    md.push_str("\n## Time in Warning/Critical\n\n");
+    if summary.severity_times.is_empty() {
+        md.push_str("No threshold was crossed.\n");
+    } else {
+        md.push_str("| Threshold | Warning | Critical | Share of recording |\n");
+        md.push_str("|-----------|--------:|---------:|-------------------:|\n");
+        for time in &summary.severity_times {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {:.1}% |",
+                time.name,
+                format_duration(time.warning_secs.round() as i64),
+                format_duration(time.critical_secs.round() as i64),
+                share(
+                    time.warning_secs + time.critical_secs,
+                    summary.recorded_secs
+                )
+            );
+        }
+    }
+
+    md.push_str("\n## Top Recommendations\n\n");
+    if summary.firings.is_empty() {
+        md.push_str("No recommendations fired.\n");
+    } else {
+        md.push_str("| Severity | Recommendation | Active | Fired | Advice |\n");
+        md.push_str("|----------|----------------|-------:|-------|--------|\n");
+        for firing in &summary.firings {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} | {} | {} |",
+                firing.severity.name(),
+                firing.title,
+                format_duration(firing.active_secs.round() as i64),
+                format_episodes(&firing.episodes),
+                firing.advice.replace('|', "\\|")
+            );
+        }
+    }
+
+    md.push_str("\n## Charts\n\n");
+    match chart_dir {
+        Some(dir) => {
+            let dir_name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            for (title, _) in &summary.charts {
+                let _ = writeln!(
+                    md,
+                    "![{}]({}/{})\n",
+                    title.replace('[', "(").replace(']', ")"),
+                    dir_name,
+                    chart_file_name(title)
+                );
+            }
+        }
+        None => md.push_str("Charts are written next to the report when using `--output`.\n"),
+    }
+    md
+}
+
+/// Render the report as a self-contained HTML page.
+fn render_html(summary: &Summary) -> String {
+    let mut html = String::new();
+    let title = format!("slow-rs report: {}", summary.file);
+    let _ = writeln!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>",
+        escape_html(&title)
+    );
+    html.push_str(
+        "<style>\
+         body{font-family:sans-serif;margin:2em auto;max-width:760px;color:#222}\
+         table{border-collapse:collapse;margin-bottom:1em}\
+         th,td{border:1px solid #ddd;padding:3px 8px;font-size:14px}\
+         td.n{text-align:right}\
+         .critical{color:#d00000;font-weight:bold}.warning{color:#b07d00;font-weight:bold}\
+         svg{display:block;margin-bottom:1em}\
+         </style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(html, "<h1>{}</h1>", escape_html(&title));
+    let _ = writeln!(
+        html,
+        "<p>{} samples from {} to {} UTC ({} recorded).</p>",
+        summary.sample_count,
+        format_time(summary.span.0),
+        format_time(summary.span.1),
+        format_duration(summary.recorded_secs.round() as i64)
+    );
+
+    html.push_str("<h2>Metrics</h2>\n<table>\n<tr><th>Metric</th><th>Unit</th><th>Min</th><th>Avg</th><th>p95</th><th>Max</th><th>Samples</th></tr>\n");
+    for ((name, unit, _), stats) in &summary.stats {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td></tr>",
+            escape_html(name),
+            escape_html(unit),
+            format_value(stats.min),
+            format_value(stats.avg),
+            format_value(stats.p95),
+            format_value(stats.max),
+            stats.count
+        );
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Time in Warning/Critical</h2>\n");
+    if summary.severity_times.is_empty() {
+        html.push_str("<p>No threshold was crossed.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Threshold</th><th>Warning</th><th>Critical</th><th>Share of recording</th></tr>\n");
+        for time in &summary.severity_times {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"n\">{}</td><td class=\"n\">{}</td><td class=\"n\">{:.1}%</td></tr>",
+                escape_html(time.name),
+                format_duration(time.warning_secs.round() as i64),
+                format_duration(time.critical_secs.round() as i64),
+                share(time.warning_secs + time.critical_secs, summary.recorded_secs)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Top Recommendations</h2>\n");
+    if summary.firings.is_empty() {
+        html.push_str("<p>No recommendations fired.</p>\n");
+    } else {
+        html.push_str("<table>\n<tr><th>Severity</th><th>Recommendation</th><th>Active</th><th>Fired</th><th>Advice</th></tr>\n");
+        for firing in &summary.firings {
+            let _ = writeln!(
+                html,
+                "<tr><td class=\"{0}\">{0}</td><td>{1}</td><td class=\"n\">{2}</td><td>{3}</td><td>{4}</td></tr>",
+                firing.severity.name(),
+                escape_html(&firing.title),
+                format_duration(firing.active_secs.round() as i64),
+                escape_html(&format_episodes(&firing.episodes)),
+                escape_html(&firing.advice)
+            );
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Charts</h2>\n");
+    for (_, svg) in &summary.charts {
+        html.push_str(svg);
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// List when a recommendation was active (first few periods, UTC).
+fn format_episodes(episodes: &[(i64, i64)]) -> String {
+    const SHOWN: usize = 3;
+    let mut parts: Vec<String> = episodes
+        .iter()
+        .take(SHOWN)
+        .map(|&(start, end)| format!("{}–{}", format_time(start), format_clock(end)))
+        .collect();
+    if episodes.len() > SHOWN {
+        parts.push(format!("+{} more", episodes.len() - SHOWN));
+    }
+    parts.join(", ")
+}
+
+/// Share of `total` in percent.
+fn share(part: f64, total: f64) -> f64 {
+    if total > 0.0 {
+        part / total * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Format a Unix timestamp as UTC date and time.
+fn format_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Format a Unix timestamp as UTC time of day.
+fn format_clock(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.format("%H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
+/// Escape text for HTML and SVG.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,92 @@
+//! Structured output sinks for slow-rs.
+//!
+//! The CSV log is flat, so it can't hold nested data such as the IPMI
+//! sensor lists or per-device disk and network counters. Sinks receive
+//! every collected [`Metrics`] sample and write it in a structured format
+//! for log shippers and time-series databases.
+//!
+//! # Sinks
+//!
+//! - [`JsonlSink`]: one JSON object per line (`--jsonl-file`)
+//! - [`crate::influx::InfluxSink`]: InfluxDB line protocol (`--influx-output`)
+
+use std::collections::BTreeMap;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
+
+use serde::Serialize;
+
+use crate::collectors::{DiskStats, NetStats};
+use crate::metrics::{IpmiDimmTemp, IpmiTempReading, Metrics};
+
+/// Destination for collected samples.
+pub trait MetricsSink {
+    /// Name shown when writing fails (e.g. "JSON Lines").
+    fn name(&self) -> &'static str;
+
+    /// Write one sample.
+    fn write(&mut self, metrics: &Metrics) -> std::io::Result<()>;
+}
+
+/// Open a file for appending, or stdout for `-`.
+pub fn open_output(path: &str) -> std::io::Result<Box<dyn Write>> {
+    if path == "-" {
+        Ok(Box::new(std::io::stdout()))
+    } else {
+        let file = OpenOptions::new().append(true).create(true).open(path)?;
+        Ok(Box::new(BufWriter::new(file)))
+    }
+}
+
+/// A sample as written to JSON Lines: all [`Metrics`] fields plus the
+/// IPMI sensor lists and per-device data that the CSV leaves out.
+#[derive(Serialize)]
+struct JsonRecord<'a> {
+    /// All scalar and nested fields of the sample
+    #[serde(flatten)]
+    metrics: &'a Metrics,
+    /// Individual IPMI DIMM temperatures
+    ipmi_dimm_temps: &'a [IpmiDimmTemp],
+    /// All IPMI temperature sensors
+    ipmi_temps: &'a [IpmiTempReading],
+    /// Per-disk I/O deltas, keyed by device
+    disk_devices: &'a BTreeMap<String, DiskStats>,
+    /// Per-interface network deltas, keyed by interface
+    net_interfaces: &'a BTreeMap<String, NetStats>,
+}
+
+/// Writes one JSON object per sample (newline-delimited JSON).
+pub struct JsonlSink {
+    /// Output file or stdout
+    writer: Box<dyn Write>,
+}
+
+impl JsonlSink {
+    /// Open a JSON Lines sink (appends to `path`, `-` for stdout).
+    pub fn open(path: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: open_output(path)?,
+        })
+    }
+}
+
+impl MetricsSink for JsonlSink {
+    fn name(&self) -> &'static str {
+        "JSON Lines"
+    }
+
+    fn write(&mut self, metrics: &Metrics) -> std::io::Result<()> {
+        let record = JsonRecord {
+            metrics,
+            ipmi_dimm_temps: &metrics.ipmi_dimm_temps,
+            ipmi_temps: &metrics.ipmi_temps,
+            disk_devices: &metrics.disk_devices,
+            net_interfaces: &metrics.net_interfaces,
+        };
+        serde_json::to_writer(&mut self.writer, &record)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        self.writer.write_all(b"\n")?;
+        // Flush every line so log shippers tailing the file see it immediately
+        self.writer.flush()
+    }
+}
@@ -3,8 +3,13 @@
 //! This module defines severity levels and threshold values for
 //! determining when metrics should trigger warnings or critical alerts.
 
-/// Severity level for a metric.
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Severity level for a metric (ordered from least to most severe).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Severity {
     /// Normal operating range
     #[default]
@@ -15,6 +20,17 @@ pub enum Severity {
     Critical,
 }
 
+impl Severity {
+    /// Lowercase name, as stored in logs and databases.
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Normal => "normal",
+            Severity::Warning => "warning",
+            Severity::Critical => "critical",
+        }
+    }
+}
+
 /// Threshold configuration for all monitored metrics.
 #[derive(Clone, Debug)]
 pub struct Thresholds {
@@ -50,6 +66,30 @@ pub struct Thresholds {
     pub iowait_warning: f64,
     /// I/O wait percentage critical threshold
     pub iowait_critical: f64,
+    /// Storage p99 latency warning threshold (ms), applied to random read,
+    /// random write, fsync and fdatasync p99
+    pub io_latency_p99_warning_ms: f64,
+    /// Storage p99 latency critical threshold (ms), applied like the warning
+    pub io_latency_p99_critical_ms: f64,
+    /// Scheduler wakeup p99 latency warning threshold (µs)
+    pub wakeup_latency_warning_us: f64,
+    /// Scheduler wakeup p99 latency critical threshold (µs)
+    pub wakeup_latency_critical_us: f64,
+    /// Slowdown factor versus baseline that counts as a regression
+    pub baseline_regression_factor: f64,
+    /// Benchmark slowdown ratio (recent vs. earlier samples) considered "rising"
+    pub benchmark_slowdown_warning: f64,
+    /// Per-core compute time relative to the median that marks a core as slow
+    pub slow_core_factor: f64,
+    /// Multi-core scaling efficiency warning threshold (%, relative to the
+    /// physical cores so SMT siblings don't halve it)
+    pub scaling_efficiency_warning: f64,
+    /// Robust z-score versus the metric's own history that counts as a warning anomaly
+    pub anomaly_z_warning: f64,
+    /// Robust z-score versus the metric's own history that counts as a critical anomaly
+    pub anomaly_z_critical: f64,
+    /// Earlier samples needed before a metric is checked for anomalies
+    pub anomaly_min_samples: usize,
 }
 
 impl Default for Thresholds {
@@ -71,11 +111,30 @@ impl Default for Thresholds {
             mem_pressure_critical: 25.0,
             iowait_warning: 20.0,
             iowait_critical: 40.0,
+            io_latency_p99_warning_ms: 50.0,
+            io_latency_p99_critical_ms: 200.0,
+            wakeup_latency_warning_us: 2000.0,
+            wakeup_latency_critical_us: 10000.0,
+            baseline_regression_factor: 1.5,
+            benchmark_slowdown_warning: 1.25,
+            slow_core_factor: 1.3,
+            scaling_efficiency_warning: 50.0,
+            anomaly_z_warning: 3.5,
+            anomaly_z_critical: 8.0,
+            anomaly_min_samples: 10,
         }
     }
 }
 
 impl Thresholds {
+    /// Default thresholds with the command-line overrides applied.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            baseline_regression_factor: config.regression_factor,
+            ..Self::default()
+        }
+    }
+
     /// Evaluate I/O pressure severity.
     pub fn io_pressure_severity(&self, value: f64) -> Severity {
         if value >= self.io_pressure_critical {
@@ -163,4 +222,27 @@ impl Thresholds {
             Severity::Normal
         }
     }
+
+    /// Evaluate storage p99 latency severity (random read/write, fsync or
+    /// fdatasync).
+    pub fn io_latency_severity(&self, p99_ms: f64) -> Severity {
+        if p99_ms >= self.io_latency_p99_critical_ms {
+            Severity::Critical
+        } else if p99_ms >= self.io_latency_p99_warning_ms {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        }
+    }
+
+    /// Evaluate scheduler wakeup latency severity.
+    pub fn wakeup_latency_severity(&self, p99_us: f64) -> Severity {
+        if p99_us >= self.wakeup_latency_critical_us {
+            Severity::Critical
+        } else if p99_us >= self.wakeup_latency_warning_us {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        }
+    }
 }
@@ -3,15 +3,17 @@
 //! This module provides a real-time dashboard using `ratatui` that displays:
 //!
 //! - Status bar with current metrics summary
-//! - Four charts showing key metrics over time
+//! - Charts showing key metrics over time (system page and benchmark page)
 //! - Detailed metrics panels at the bottom
 //!
 //! # Controls
 //!
 //! - `q` or `Esc`: Quit
+//! - `Tab`: Switch between system and benchmark charts
 //! - `Up`/`Down`: Scroll (reserved for future use)
 
 use std::collections::VecDeque;
+use std::io::Write;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -26,7 +28,7 @@ use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
-    text::Span,
+    text::{Line, Span},
     widgets::{
         Axis, Block, BorderType, Borders, Chart, Dataset, GraphType, LegendPosition, List,
         ListItem, Paragraph,
@@ -34,10 +36,14 @@ use ratatui::{
     Frame, Terminal,
 };
 
+use crate::anomaly::{detect_anomalies, Anomaly};
 use crate::app::App;
 use crate::availability::MetricAvailability;
-use crate::metrics::Metrics;
+use crate::diagnosis::{diagnose, Diagnosis};
+use crate::incidents::Incident;
+use crate::metrics::{format_duration, parse_labeled, Metrics};
 use crate::recommendations::{generate_recommendations, Recommendation};
+use crate::replay::Playback;
 use crate::thresholds::{Severity, Thresholds};
 
 /// Run the TUI event loop.
@@ -51,35 +57,41 @@ use crate::thresholds::{Severity, Thresholds};
 /// * `running` - Atomic flag to signal shutdown
 /// * `interval` - Time between metric collections
 pub fn run(mut app: App, running: Arc<AtomicBool>, interval: Duration) -> std::io::Result<()> {
-    let history_size = app.config.history_size;
-
     enable_raw_mode()?;
     if let Err(e) = std::io::stdout().execute(EnterAlternateScreen) {
         let _ = disable_raw_mode();
         return Err(e);
     }
 
-    let result = run_tui_loop(&mut app, &running, interval, history_size);
+    let result = run_tui_loop(&mut app, &running, interval);
 
     // Always clean up terminal state
     let _ = disable_raw_mode();
     let _ = std::io::stdout().execute(LeaveAlternateScreen);
 
+    save_incidents(&app);
     result
 }
 
+/// Save the incident file on exit, so open incidents keep their last sample.
+fn save_incidents(app: &App) {
+    if let Err(e) = app.incidents.save() {
+        eprintln!("Warning: Incident file failed: {}", e);
+    }
+}
+
 /// Inner TUI loop - separated to ensure cleanup happens on any exit path.
 fn run_tui_loop(
     app: &mut App,
     running: &Arc<AtomicBool>,
     interval: Duration,
-    history_size: usize,
 ) -> std::io::Result<()> {
     let backend = CrosstermBackend::new(std::io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     let mut last_collection = Instant::now();
     let mut _scroll_offset = 0usize;
+    let mut page = ChartPage::default();
 
     // Draw loading screen immediately so user sees something
     terminal.draw(|f| {
@@ -87,9 +99,7 @@ fn run_tui_loop(
     })?;
 
     // Initial collection (this is the slow part)
-    if let Ok(metrics) = app.collect_metrics() {
-        add_metrics(&mut app.metrics_history, metrics, history_size);
-    }
+    let _ = app.collect_metrics();
 
     while running.load(Ordering::Relaxed) {
         // Check for input
@@ -103,6 +113,9 @@ fn run_tui_loop(
                         KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                             running.store(false, Ordering::Relaxed);
                         }
+                        KeyCode::Tab => {
+                            page = page.next();
+                        }
                         KeyCode::Up => {
                             _scroll_offset = _scroll_offset.saturating_sub(1);
                         }
@@ -117,77 +130,192 @@ fn run_tui_loop(
 
         // Collect metrics at interval
         if last_collection.elapsed() >= interval {
-            if let Ok(metrics) = app.collect_metrics() {
-                add_metrics(&mut app.metrics_history, metrics, history_size);
-            }
+            let _ = app.collect_metrics();
             last_collection = Instant::now();
         }
 
         // Draw UI
-        terminal.draw(|f| draw_ui(f, &app.metrics_history, &app.availability, &app.thresholds))?;
+        terminal.draw(|f| {
+            draw_ui(
+                f,
+                &app.metrics_history,
+                &app.warnings(),
+                &app.thresholds,
+                page,
+                None,
+                Some(app.incidents.incidents()),
+            )
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Play back a recording in the TUI until the user quits.
+///
+/// See [`crate::replay`] for the controls.
+pub fn run_replay(
+    mut playback: Playback,
+    thresholds: &Thresholds,
+    running: Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    enable_raw_mode()?;
+    if let Err(e) = std::io::stdout().execute(EnterAlternateScreen) {
+        let _ = disable_raw_mode();
+        return Err(e);
+    }
+
+    let result = run_replay_loop(&mut playback, thresholds, &running);
+
+    // Always clean up terminal state
+    let _ = disable_raw_mode();
+    let _ = std::io::stdout().execute(LeaveAlternateScreen);
+
+    result
+}
+
+/// Inner replay loop - separated to ensure cleanup happens on any exit path.
+fn run_replay_loop(
+    playback: &mut Playback,
+    thresholds: &Thresholds,
+    running: &Arc<AtomicBool>,
+) -> std::io::Result<()> {
+    let backend = CrosstermBackend::new(std::io::stdout());
+    let mut terminal = Terminal::new(backend)?;
+
+    // Availability describes the machine that recorded, which is unknown here
+    let warnings = MetricAvailability::default().get_warnings();
+    let mut page = ChartPage::default();
+
+    while running.load(Ordering::Relaxed) {
+        if event::poll(Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    match key.code {
+                        KeyCode::Char('q') | KeyCode::Esc => {
+                            running.store(false, Ordering::Relaxed);
+                        }
+                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                            running.store(false, Ordering::Relaxed);
+                        }
+                        // Incidents are only tracked while monitoring live
+                        KeyCode::Tab => {
+                            page = match page.next() {
+                                ChartPage::Incidents => ChartPage::System,
+                                next => next,
+                            }
+                        }
+                        KeyCode::Char(' ') => playback.toggle_pause(),
+                        KeyCode::Left => playback.seek_by(-1),
+                        KeyCode::Right => playback.seek_by(1),
+                        KeyCode::PageUp => playback.seek_fraction(-0.1),
+                        KeyCode::PageDown => playback.seek_fraction(0.1),
+                        KeyCode::Home => playback.seek_to(0),
+                        KeyCode::End => playback.seek_to(usize::MAX),
+                        KeyCode::Char('+') | KeyCode::Char('=') => playback.change_speed(2.0),
+                        KeyCode::Char('-') => playback.change_speed(0.5),
+                        KeyCode::Char('z') => playback.toggle_zoom(),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        playback.tick();
+
+        terminal.draw(|f| {
+            draw_ui(
+                f,
+                playback.window(),
+                &warnings,
+                thresholds,
+                page,
+                Some(playback),
+                None,
+            )
+        })?;
     }
 
     Ok(())
 }
 
-/// Add metrics to history, maintaining max size.
-fn add_metrics(history: &mut VecDeque<Metrics>, metrics: Metrics, max_size: usize) {
-    if history.len() >= max_size {
-        history.pop_front();
+/// Which set of charts is shown in the chart area.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+enum ChartPage {
+    /// System metrics from /proc, PSI and sensors
+    #[default]
+    System,
+    /// Active benchmark results
+    Benchmarks,
+    /// Incident timeline (live monitoring only)
+    Incidents,
+}
+
+impl ChartPage {
+    /// Cycle to the next page.
+    fn next(self) -> Self {
+        match self {
+            ChartPage::System => ChartPage::Benchmarks,
+            ChartPage::Benchmarks => ChartPage::Incidents,
+            ChartPage::Incidents => ChartPage::System,
+        }
+    }
+
+    /// Short label for the status bar.
+    fn label(self) -> &'static str {
+        match self {
+            ChartPage::System => "System",
+            ChartPage::Benchmarks => "Benchmarks",
+            ChartPage::Incidents => "Incidents",
+        }
     }
-    history.push_back(metrics);
 }
 
 /// Main UI drawing function.
 fn draw_ui(
     f: &mut Frame,
     metrics_history: &VecDeque<Metrics>,
-    availability: &MetricAvailability,
+    warnings: &[String],
     thresholds: &Thresholds,
+    page: ChartPage,
+    replay: Option<&Playback>,
+    incidents: Option<&[Incident]>,
 ) {
     let size = f.area();
 
     // Check if we have warnings to show
-    let warnings = availability.get_warnings();
     let has_warnings = !warnings.is_empty();
 
     // Generate recommendations from latest metrics
     let recommendations = metrics_history
         .back()
-        .map(|m| generate_recommendations(m, thresholds))
+        .map(|m| generate_recommendations(m, metrics_history, thresholds))
         .unwrap_or_default();
     let has_recommendations = !recommendations.is_empty();
+    let anomalies = metrics_history
+        .back()
+        .map(|m| detect_anomalies(m, metrics_history, thresholds))
+        .unwrap_or_default();
+    let diagnoses = metrics_history
+        .back()
+        .map(|m| diagnose(m, metrics_history, thresholds))
+        .unwrap_or_default();
+    let has_diagnosis = !diagnoses.is_empty();
 
-    // Main layout: status bar, [warnings], charts, [recommendations], details
-    let constraints = if has_warnings && has_recommendations {
-        vec![
-            Constraint::Length(3),  // Status bar
-            Constraint::Length(1),  // Warnings bar
-            Constraint::Min(18),    // Charts (3x2)
-            Constraint::Length(3),  // Recommendations
-            Constraint::Length(10), // Detailed metrics
-        ]
-    } else if has_warnings {
-        vec![
-            Constraint::Length(3),  // Status bar
-            Constraint::Length(1),  // Warnings bar
-            Constraint::Min(18),    // Charts
-            Constraint::Length(10), // Detailed metrics
-        ]
-    } else if has_recommendations {
-        vec![
-            Constraint::Length(3),  // Status bar
-            Constraint::Min(18),    // Charts
-            Constraint::Length(3),  // Recommendations
-            Constraint::Length(10), // Detailed metrics
-        ]
-    } else {
-        vec![
-            Constraint::Length(3),  // Status bar
-            Constraint::Min(18),    // Charts
-            Constraint::Length(10), // Detailed metrics
-        ]
-    };
+    // Main layout: status bar, [diagnosis], [warnings], charts,
+    // [recommendations], details
+    let mut constraints = vec![Constraint::Length(3)]; // Status bar
+    if has_diagnosis {
+        constraints.push(Constraint::Length(3)); // Diagnosis
+    }
+    if has_warnings {
+        constraints.push(Constraint::Length(1)); // Warnings bar
+    }
+    constraints.push(Constraint::Min(18)); // Charts (3x3)
+    if has_recommendations {
+        constraints.push(Constraint::Length(3)); // Recommendations
+    }
+    constraints.push(Constraint::Length(10)); // Detailed metrics
 
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -197,17 +325,44 @@ fn draw_ui(
     let mut chunk_idx = 0;
 
     // Status bar
-    draw_status_bar(f, metrics_history, main_chunks[chunk_idx]);
+    draw_status_bar(f, metrics_history, page, replay, main_chunks[chunk_idx]);
     chunk_idx += 1;
 
+    // Likely causes (if any)
+    if has_diagnosis {
+        draw_diagnosis(f, &diagnoses, main_chunks[chunk_idx]);
+        chunk_idx += 1;
+    }
+
     // Warnings bar (if present)
     if has_warnings {
-        draw_warnings(f, &warnings, main_chunks[chunk_idx]);
+        draw_warnings(f, warnings, main_chunks[chunk_idx]);
         chunk_idx += 1;
     }
 
     // Charts
-    draw_charts(f, metrics_history, thresholds, main_chunks[chunk_idx]);
+    match page {
+        ChartPage::System => draw_charts(
+            f,
+            metrics_history,
+            thresholds,
+            &anomalies,
+            main_chunks[chunk_idx],
+        ),
+        ChartPage::Benchmarks => draw_benchmark_charts(
+            f,
+            metrics_history,
+            thresholds,
+            &anomalies,
+            main_chunks[chunk_idx],
+        ),
+        ChartPage::Incidents => draw_incidents(
+            f,
+            incidents.unwrap_or_default(),
+            metrics_history.back().map_or(0, |m| m.timestamp),
+            main_chunks[chunk_idx],
+        ),
+    }
     chunk_idx += 1;
 
     // Recommendations (if present)
@@ -257,10 +412,30 @@ fn draw_loading_screen(f: &mut Frame) {
 }
 
 /// Draw the top status bar.
-fn draw_status_bar(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rect) {
-    let status_text = if let Some(m) = metrics_history.back() {
+fn draw_status_bar(
+    f: &mut Frame,
+    metrics_history: &VecDeque<Metrics>,
+    page: ChartPage,
+    replay: Option<&Playback>,
+    area: Rect,
+) {
+    let status_text = if let (Some(m), Some(playback)) = (metrics_history.back(), replay) {
         format!(
-            " 📊 slow-rs | {} | CPU: {:.1}% | Mem: {}/{} MB | Load: {:.2} {:.2} {:.2} | Samples: {} | [q]uit",
+            " {} replay | {} | CPU: {:.1}% | Mem: {}/{} MB | Sample {}/{} | {}x{} | [Space] [←→] [PgUp/PgDn] [+/-] [z]oom | [Tab] {} | [q]uit",
+            if playback.paused { "⏸" } else { "▶" },
+            m.datetime,
+            m.cpu_usage_percent,
+            m.mem_used_mb,
+            m.mem_total_mb,
+            playback.position + 1,
+            playback.sample_count(),
+            playback.speed,
+            if playback.zoomed { " | full range" } else { "" },
+            page.label()
+        )
+    } else if let Some(m) = metrics_history.back() {
+        format!(
+            " 📊 slow-rs | {} | CPU: {:.1}% | Mem: {}/{} MB | Load: {:.2} {:.2} {:.2} | Samples: {} | [Tab] {} | [q]uit",
             m.datetime,
             m.cpu_usage_percent,
             m.mem_used_mb,
@@ -268,7 +443,8 @@ fn draw_status_bar(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rec
             m.load_avg_1,
             m.load_avg_5,
             m.load_avg_15,
-            metrics_history.len()
+            metrics_history.len(),
+            page.label()
         )
     } else {
         " 📊 slow-rs | Collecting initial metrics... | [q]uit".to_string()
@@ -286,7 +462,7 @@ fn draw_status_bar(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rec
     f.render_widget(status, area);
 }
 
-/// Draw the warnings bar for unavailable metrics.
+/// Draw the warnings bar for failing outputs and unavailable metrics.
 fn draw_warnings(f: &mut Frame, warnings: &[String], area: Rect) {
     let text = warnings.join(" | ");
     let paragraph = Paragraph::new(text)
@@ -295,12 +471,47 @@ fn draw_warnings(f: &mut Frame, warnings: &[String], area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_type(BorderType::Rounded)
-                .title("⚠ Limited Metrics")
+                .title("⚠ Warnings")
                 .border_style(Style::default().fg(Color::Yellow)),
         );
     f.render_widget(paragraph, area);
 }
 
+/// Draw the ranked likely causes, with evidence for the most likely one.
+fn draw_diagnosis(f: &mut Frame, diagnoses: &[Diagnosis], area: Rect) {
+    let top = match diagnoses.first() {
+        Some(d) => d,
+        None => return,
+    };
+
+    let color = if top.confidence >= 0.7 {
+        Color::Red
+    } else {
+        Color::Yellow
+    };
+
+    let mut spans = vec![Span::styled(
+        format!(" {} {:.0}%", top.cause.name(), top.confidence * 100.0),
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    )];
+    spans.push(Span::raw(format!(": {}", top.evidence.join(", "))));
+    for other in &diagnoses[1..] {
+        spans.push(Span::styled(
+            format!(" | {} {:.0}%", other.cause.name(), other.confidence * 100.0),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+
+    let paragraph = Paragraph::new(Line::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .title("🔎 Likely Cause")
+            .border_style(Style::default().fg(color)),
+    );
+    f.render_widget(paragraph, area);
+}
+
 /// Draw the recommendations panel showing ALL critical issues.
 fn draw_recommendations(f: &mut Frame, recommendations: &[Recommendation], area: Rect) {
     if recommendations.is_empty() {
@@ -362,6 +573,7 @@ fn draw_charts(
     f: &mut Frame,
     metrics_history: &VecDeque<Metrics>,
     thresholds: &Thresholds,
+    anomalies: &[Anomaly],
     area: Rect,
 ) {
     if metrics_history.is_empty() {
@@ -377,6 +589,7 @@ fn draw_charts(
 
     // Get latest metrics for severity calculation
     let latest = metrics_history.back().unwrap();
+    let unusual = |key: &str| anomalies.iter().any(|a| a.check.key == key);
 
     // 3 rows of charts (3x3 grid)
     let rows = Layout::default()
@@ -424,6 +637,7 @@ fn draw_charts(
         |m| m.io_read_mb_per_sec.unwrap_or(0.0),
         ChartConfig {
             color: Color::Cyan,
+            anomaly: unusual("io_read"),
             ..Default::default()
         },
     );
@@ -452,6 +666,7 @@ fn draw_charts(
             severity: cpu_severity,
             warning: Some(thresholds.cpu_usage_warning as f64),
             critical: Some(thresholds.cpu_usage_critical as f64),
+            anomaly: unusual("cpu_usage"),
         },
     );
 
@@ -468,6 +683,7 @@ fn draw_charts(
             severity: mem_severity,
             warning: Some(thresholds.memory_available_warning_mb as f64),
             critical: Some(thresholds.memory_available_critical_mb as f64),
+            anomaly: unusual("mem_available"),
         },
     );
 
@@ -484,6 +700,7 @@ fn draw_charts(
             severity: io_pressure_severity,
             warning: Some(thresholds.io_pressure_warning),
             critical: Some(thresholds.io_pressure_critical),
+            anomaly: unusual("io_pressure"),
         },
     );
 
@@ -504,6 +721,7 @@ fn draw_charts(
             severity: cpu_temp_severity,
             warning: Some(thresholds.cpu_temp_warning),
             critical: Some(thresholds.cpu_temp_critical),
+            anomaly: unusual("cpu_temp"),
         },
     );
 
@@ -530,6 +748,7 @@ fn draw_charts(
             severity: dimm_severity,
             warning: Some(thresholds.dimm_temp_warning),
             critical: Some(thresholds.dimm_temp_critical),
+            anomaly: unusual("dimm_temp"),
         },
     );
 
@@ -555,6 +774,7 @@ fn draw_charts(
             severity: disk_severity,
             warning: Some(thresholds.disk_temp_warning),
             critical: Some(thresholds.disk_temp_critical),
+            anomaly: unusual("disk_temp"),
         },
     );
 
@@ -562,6 +782,380 @@ fn draw_charts(
     draw_ipmi_temps_chart(f, metrics_history, thresholds, row3[2]);
 }
 
+/// Width of the timeline lane in the incident list.
+const INCIDENT_LANE_WIDTH: usize = 30;
+
+/// Draw the incident timeline, most recent first.
+///
+/// Each row has a lane spanning from the oldest listed incident to `now`,
+/// filled where the incident was active.
+fn draw_incidents(f: &mut Frame, incidents: &[Incident], now: i64, area: Rect) {
+    let open = incidents.iter().filter(|i| i.is_open()).count();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(format!(
+            "Incidents ({} open, {} recorded, most recent first)",
+            open,
+            incidents.len()
+        ))
+        .border_style(Style::default().fg(if open > 0 {
+            Color::Yellow
+        } else {
+            Color::Reset
+        }));
+
+    if incidents.is_empty() {
+        f.render_widget(Paragraph::new(" No incidents recorded").block(block), area);
+        return;
+    }
+
+    let rows = area.height.saturating_sub(2) as usize;
+    let shown: Vec<&Incident> = incidents.iter().rev().take(rows).collect();
+    let start = shown.iter().map(|i| i.opened).min().unwrap_or(now);
+    let span = (now - start).max(1) as f64;
+    let column = |timestamp: i64| {
+        (((timestamp - start) as f64 / span) * INCIDENT_LANE_WIDTH as f64) as usize
+    };
+
+    let items: Vec<ListItem> = shown
+        .iter()
+        .map(|incident| {
+            let color = match incident.peak_severity {
+                Severity::Critical => Color::Red,
+                Severity::Warning => Color::Yellow,
+                Severity::Normal => Color::Gray,
+            };
+            let first = column(incident.opened).min(INCIDENT_LANE_WIDTH - 1);
+            let last = column(incident.closed.unwrap_or(now)).clamp(first, INCIDENT_LANE_WIDTH - 1);
+            let lane: String = (0..INCIDENT_LANE_WIDTH)
+                .map(|c| {
+                    if (first..=last).contains(&c) {
+                        '█'
+                    } else {
+                        '·'
+                    }
+                })
+                .collect();
+            let status = if incident.is_open() {
+                "ongoing".to_string()
+            } else {
+                format_duration(incident.duration_secs(now))
+            };
+
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if incident.is_open() { "● " } else { "○ " },
+                    Style::default().fg(color),
+                ),
+                Span::raw(format!(
+                    "#{:<4} {} {:>7} ",
+                    incident.id,
+                    format_timestamp(incident.opened),
+                    status
+                )),
+                Span::styled(lane, Style::default().fg(color)),
+                Span::styled(
+                    format!(" {:<8} ", incident.peak_severity.name()),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::raw(format!("{} - {}", incident.title, incident.advice)),
+            ]))
+        })
+        .collect();
+
+    f.render_widget(List::new(items).block(block), area);
+}
+
+/// A benchmark chart: title, value extractor and styling.
+type BenchmarkChart<'a> = (&'a str, fn(&Metrics) -> Option<f64>, ChartConfig);
+
+/// A per-target benchmark chart: title and "label:value" list extractor.
+type TargetChart<'a> = (&'a str, fn(&Metrics) -> Option<&String>);
+
+/// Draw the benchmark results page.
+///
+/// Only benchmarks that produced data in the visible history are shown,
+/// laid out three per row.
+fn draw_benchmark_charts(
+    f: &mut Frame,
+    metrics_history: &VecDeque<Metrics>,
+    thresholds: &Thresholds,
+    anomalies: &[Anomaly],
+    area: Rect,
+) {
+    if metrics_history.is_empty() {
+        return;
+    }
+    let unusual = |key: &str| anomalies.iter().any(|a| a.check.key == key);
+
+    // Severity follows the most recent run of each benchmark
+    let last =
+        |value_fn: fn(&Metrics) -> Option<f64>| metrics_history.iter().rev().find_map(value_fn);
+
+    let latency_config = |color: Color, latest_ms: Option<f64>, anomaly: bool| ChartConfig {
+        color,
+        anomaly,
+        severity: latest_ms
+            .map(|ms| thresholds.io_latency_severity(ms))
+            .unwrap_or_default(),
+        warning: Some(thresholds.io_latency_p99_warning_ms),
+        critical: Some(thresholds.io_latency_p99_critical_ms),
+    };
+
+    let baseline_warning = 100.0 / thresholds.baseline_regression_factor;
+    let baseline_critical = baseline_warning / thresholds.baseline_regression_factor;
+
+    let charts: Vec<BenchmarkChart> = vec![
+        (
+            "Baseline % [worst]",
+            |m| m.baseline_worst_pct,
+            ChartConfig {
+                color: Color::White,
+                severity: match last(|m| m.baseline_worst_pct) {
+                    Some(pct) if pct < baseline_critical => Severity::Critical,
+                    Some(pct) if pct < baseline_warning => Severity::Warning,
+                    _ => Severity::Normal,
+                },
+                warning: Some(baseline_warning),
+                critical: Some(baseline_critical),
+                anomaly: false,
+            },
+        ),
+        (
+            "Compute ms [SHA256]",
+            |m| m.compute_duration_ms,
+            ChartConfig {
+                color: Color::Yellow,
+                anomaly: unusual("compute"),
+                ..Default::default()
+            },
+        ),
+        (
+            "Alloc ms [64MB]",
+            |m| m.memory_alloc_duration_ms,
+            ChartConfig {
+                color: Color::Green,
+                ..Default::default()
+            },
+        ),
+        (
+            "Scaling % [all cores]",
+            |m| m.compute_scaling_efficiency,
+            ChartConfig {
+                color: Color::LightYellow,
+                ..Default::default()
+            },
+        ),
+        (
+            "Wakeup p99 µs [timer]",
+            |m| m.wakeup_p99_us,
+            ChartConfig {
+                color: Color::LightBlue,
+                severity: last(|m| m.wakeup_p99_us)
+                    .map(|us| thresholds.wakeup_latency_severity(us))
+                    .unwrap_or_default(),
+                warning: Some(thresholds.wakeup_latency_warning_us),
+                critical: Some(thresholds.wakeup_latency_critical_us),
+                anomaly: unusual("wakeup_p99"),
+            },
+        ),
+        (
+            "Triad GB/s [STREAM]",
+            |m| m.mem_triad_gb_per_sec,
+            ChartConfig {
+                color: Color::Cyan,
+                ..Default::default()
+            },
+        ),
+        (
+            "DRAM ns [pointer chase]",
+            |m| m.mem_latency_dram_ns,
+            ChartConfig {
+                color: Color::Magenta,
+                ..Default::default()
+            },
+        ),
+        (
+            "Rand Read IOPS [4K]",
+            |m| m.iops_read,
+            ChartConfig {
+                color: Color::LightCyan,
+                ..Default::default()
+            },
+        ),
+        (
+            "Rand Read p99 ms [4K]",
+            |m| m.iops_read_p99_us.map(|us| us / 1000.0),
+            latency_config(
+                Color::LightRed,
+                last(|m| m.iops_read_p99_us).map(|us| us / 1000.0),
+                unusual("iops_read_p99"),
+            ),
+        ),
+        (
+            "Create ops/s [metadata]",
+            |m| m.meta_create_ops_per_sec,
+            ChartConfig {
+                color: Color::LightGreen,
+                ..Default::default()
+            },
+        ),
+        (
+            "fsync p99 ms",
+            |m| m.fsync_p99_ms,
+            latency_config(Color::Red, last(|m| m.fsync_p99_ms), unusual("fsync_p99")),
+        ),
+        (
+            "fdatasync p99 ms",
+            |m| m.fdatasync_p99_ms,
+            latency_config(Color::LightMagenta, last(|m| m.fdatasync_p99_ms), false),
+        ),
+    ];
+
+    let target_charts: Vec<TargetChart> = vec![
+        ("Read MB/s per target", |m| {
+            m.target_read_mb_per_sec.as_ref()
+        }),
+        ("Write MB/s per target", |m| {
+            m.target_write_mb_per_sec.as_ref()
+        }),
+        ("Rand Read IOPS per target", |m| m.target_iops_read.as_ref()),
+        ("Rand Read p99 µs per target", |m| {
+            m.target_iops_read_p99_us.as_ref()
+        }),
+        ("fsync p99 ms per target", |m| {
+            m.target_fsync_p99_ms.as_ref()
+        }),
+        ("Create ops/s per target", |m| {
+            m.target_meta_create_ops_per_sec.as_ref()
+        }),
+    ];
+
+    let charts: Vec<BenchmarkChart> = charts
+        .into_iter()
+        .filter(|(_, value_fn, _)| metrics_history.iter().any(|m| value_fn(m).is_some()))
+        .collect();
+    let target_charts: Vec<TargetChart> = target_charts
+        .into_iter()
+        .filter(|(_, list_fn)| metrics_history.iter().any(|m| list_fn(m).is_some()))
+        .collect();
+
+    let cells = chart_grid(area, charts.len() + target_charts.len());
+    let (chart_cells, target_cells) = cells.split_at(charts.len());
+
+    for ((title, value_fn, config), cell) in charts.into_iter().zip(chart_cells) {
+        draw_sparse_line_chart(f, metrics_history, *cell, title, value_fn, config);
+    }
+    for ((title, list_fn), cell) in target_charts.into_iter().zip(target_cells) {
+        draw_target_chart(f, metrics_history, *cell, title, list_fn);
+    }
+}
+
+/// Split an area into `count` chart cells, three per row.
+fn chart_grid(area: Rect, count: usize) -> Vec<Rect> {
+    let row_count = (count + 2) / 3;
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(vec![Constraint::Ratio(1, row_count as u32); row_count])
+        .split(area);
+
+    (0..count)
+        .map(|idx| {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                ])
+                .split(rows[idx / 3]);
+            cols[idx % 3]
+        })
+        .collect()
+}
+
+/// Draw one line per `--io-target` from a "label:value,..." metrics field.
+fn draw_target_chart(
+    f: &mut Frame,
+    metrics_history: &VecDeque<Metrics>,
+    area: Rect,
+    title: &str,
+    list_fn: fn(&Metrics) -> Option<&String>,
+) {
+    let colors = [
+        Color::Cyan,
+        Color::Yellow,
+        Color::Magenta,
+        Color::Green,
+        Color::LightBlue,
+        Color::LightRed,
+    ];
+
+    // Collect a series per target label (in order of first appearance)
+    let mut series: Vec<(String, Vec<(f64, f64)>)> = Vec::new();
+    for (time_idx, metrics) in metrics_history.iter().enumerate() {
+        let Some(list) = list_fn(metrics) else {
+            continue;
+        };
+        for (label, value) in parse_labeled(list) {
+            let idx = match series.iter().position(|(l, _)| l == label) {
+                Some(idx) => idx,
+                None => {
+                    series.push((label.to_string(), Vec::new()));
+                    series.len() - 1
+                }
+            };
+            series[idx].1.push((time_idx as f64, value));
+        }
+    }
+
+    let max_y = series
+        .iter()
+        .flat_map(|(_, data)| data.iter().map(|(_, y)| *y))
+        .fold(0.0, f64::max);
+    let y_max = if max_y > 0.0 { max_y * 1.05 } else { 1.0 };
+    let data_len = metrics_history.len();
+
+    let datasets: Vec<Dataset> = series
+        .iter()
+        .enumerate()
+        .map(|(idx, (label, data))| {
+            // Include the latest value in the legend: "/home:220"
+            let current = data.last().map(|(_, y)| *y).unwrap_or(0.0);
+            Dataset::default()
+                .name(format!("{}:{:.0}", label, current))
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(colors[idx % colors.len()]))
+                .data(data)
+        })
+        .collect();
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title);
+
+    let chart = Chart::new(datasets)
+        .block(block)
+        .legend_position(Some(LegendPosition::TopRight))
+        .hidden_legend_constraints((Constraint::Min(0), Constraint::Min(0)))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .bounds([0.0, data_len as f64]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(Color::Gray))
+                .labels(vec![Span::raw("0"), Span::raw(format!("{:.0}", y_max))])
+                .bounds([0.0, y_max]),
+        );
+
+    f.render_widget(chart, area);
+}
+
 /// Draw IPMI temperature chart showing all DIMM temperatures over time.
 fn draw_ipmi_temps_chart(
     f: &mut Frame,
@@ -819,6 +1413,8 @@ struct ChartConfig {
     critical: Option<f64>,
     color: Color,
     severity: Severity,
+    /// The latest value is unusual for this metric's own history
+    anomaly: bool,
 }
 
 /// Draw a single line chart with optional severity highlighting and threshold lines.
@@ -833,23 +1429,59 @@ fn draw_line_chart<F>(
 ) where
     F: Fn(&Metrics) -> f64,
 {
+    let data: Vec<(f64, f64)> = metrics_history
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (i as f64, value_fn(m)))
+        .collect();
+
+    draw_series_chart(f, &data, metrics_history.len(), area, title, config);
+}
+
+/// Draw a chart of a value that is only present in some samples.
+///
+/// Benchmarks on their own schedule leave gaps; the line connects the
+/// samples in which they ran instead of dropping to zero in between.
+fn draw_sparse_line_chart(
+    f: &mut Frame,
+    metrics_history: &VecDeque<Metrics>,
+    area: Rect,
+    title: &str,
+    value_fn: fn(&Metrics) -> Option<f64>,
+    config: ChartConfig,
+) {
+    let data: Vec<(f64, f64)> = metrics_history
+        .iter()
+        .enumerate()
+        .filter_map(|(i, m)| Some((i as f64, value_fn(m)?)))
+        .collect();
+
+    draw_series_chart(f, &data, metrics_history.len(), area, title, config);
+}
+
+/// Draw a line chart from (time index, value) points.
+///
+/// `data_len` is the number of samples spanned by the x axis.
+fn draw_series_chart(
+    f: &mut Frame,
+    data: &[(f64, f64)],
+    data_len: usize,
+    area: Rect,
+    title: &str,
+    config: ChartConfig,
+) {
     let ChartConfig {
         warning,
         critical,
         color,
         severity,
+        anomaly,
     } = config;
-    let data: Vec<(f64, f64)> = metrics_history
-        .iter()
-        .enumerate()
-        .map(|(i, m)| (i as f64, value_fn(m)))
-        .collect();
 
     if data.is_empty() {
         return;
     }
 
-    let data_len = data.len();
     let min_y = data.iter().map(|(_, y)| *y).fold(f64::INFINITY, f64::min);
     let max_y = data
         .iter()
@@ -892,15 +1524,26 @@ fn draw_line_chart<F>(
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         ),
+        Severity::Normal if anomaly => (
+            Color::Magenta,
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ),
         Severity::Normal => (Color::Reset, Style::default()),
     };
+    let heading = if anomaly {
+        format!("{} ~unusual", title)
+    } else {
+        title.to_string()
+    };
 
     let mut datasets = vec![Dataset::default()
         .name(title)
         .marker(symbols::Marker::Braille)
         .graph_type(GraphType::Line)
         .style(Style::default().fg(color))
-        .data(&data)];
+        .data(data)];
 
     // Add warning threshold line (only if data is near threshold)
     let warning_line: Vec<(f64, f64)>;
@@ -937,7 +1580,7 @@ fn draw_line_chart<F>(
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .title(Span::styled(title, title_style))
+        .title(Span::styled(heading, title_style))
         .border_style(Style::default().fg(border_color));
 
     let chart = Chart::new(datasets)
@@ -979,6 +1622,40 @@ fn draw_line_chart<F>(
     f.render_widget(chart, area);
 }
 
+/// Print the incidents of this session, oldest first.
+fn write_incident_summary(out: &mut dyn Write, app: &App) -> std::io::Result<()> {
+    let session = app.incidents.session();
+    let now = app.metrics_history.back().map_or(0, |m| m.timestamp);
+    let history = app
+        .incidents
+        .path()
+        .map(|path| format!(" (history in {})", path.display()))
+        .unwrap_or_default();
+    writeln!(out, "Incidents this session: {}{}", session.len(), history)?;
+    for incident in session {
+        let status = if incident.is_open() {
+            "ongoing"
+        } else {
+            "closed"
+        };
+        writeln!(
+            out,
+            "  {} {:<7} {}",
+            format_timestamp(incident.opened),
+            status,
+            incident.summary(now)
+        )?;
+    }
+    Ok(())
+}
+
+/// Format a Unix timestamp as UTC date and time (like `Metrics::datetime`).
+fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| timestamp.to_string())
+}
+
 /// Draw the bottom detail panels.
 fn draw_details(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rect) {
     let latest = match metrics_history.back() {
@@ -997,25 +1674,61 @@ fn draw_details(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rect)
         .split(area);
 
     // Column 1: Benchmarks
-    let bench_items = vec![
+    // Benchmarks may not run every sample, so show the most recent run
+    let last =
+        |value_fn: fn(&Metrics) -> Option<f64>| metrics_history.iter().rev().find_map(value_fn);
+    let mut bench_items = vec![
         ListItem::new(format!(
             "Read:   {:>7.1} MB/s",
-            latest.io_read_mb_per_sec.unwrap_or(0.0)
+            last(|m| m.io_read_mb_per_sec).unwrap_or(0.0)
         )),
         ListItem::new(format!(
             "Write:  {:>7.1} MB/s",
-            latest.io_write_mb_per_sec.unwrap_or(0.0)
+            last(|m| m.io_write_mb_per_sec).unwrap_or(0.0)
         )),
         ListItem::new(format!(
             "SHA256: {:>7.1} ms",
-            latest.sha256_duration_ms.unwrap_or(0.0)
+            last(|m| m.sha256_duration_ms).unwrap_or(0.0)
         )),
         ListItem::new(format!(
             "Alloc:  {:>7.1} ms",
-            latest.memory_alloc_duration_ms
+            last(|m| m.memory_alloc_duration_ms).unwrap_or(0.0)
+        )),
+        ListItem::new(format!(
+            "Compute:{:>7.1} ms",
+            last(|m| m.compute_duration_ms).unwrap_or(0.0)
         )),
-        ListItem::new(format!("Compute:{:>7.1} ms", latest.compute_duration_ms)),
     ];
+    if let Some(ref backoff) = latest.bench_backoff {
+        bench_items.push(
+            ListItem::new(format!("Backoff: {}", backoff))
+                .style(Style::default().fg(Color::Yellow)),
+        );
+    }
+    if let Some(pct) = last(|m| m.baseline_worst_pct) {
+        bench_items.push(ListItem::new(format!("Base:   {:>7.0} %", pct)));
+    }
+    if let Some(iops) = last(|m| m.iops_read) {
+        bench_items.push(ListItem::new(format!("RandRd: {:>7.0} IOPS", iops)));
+    }
+    if let Some(p99) = last(|m| m.iops_read_p99_us) {
+        bench_items.push(ListItem::new(format!("Rd p99: {:>7.2} ms", p99 / 1000.0)));
+    }
+    if let Some(ops) = last(|m| m.meta_create_ops_per_sec) {
+        bench_items.push(ListItem::new(format!("Create: {:>7.0} op/s", ops)));
+    }
+    if let Some(p99) = last(|m| m.wakeup_p99_us) {
+        bench_items.push(ListItem::new(format!("Wakeup: {:>7.0} µs", p99)));
+    }
+    if let Some(efficiency) = last(|m| m.compute_scaling_efficiency) {
+        bench_items.push(ListItem::new(format!("Scaling:{:>7.1} %", efficiency)));
+    }
+    if let Some(triad) = last(|m| m.mem_triad_gb_per_sec) {
+        bench_items.push(ListItem::new(format!("Triad:  {:>7.1} GB/s", triad)));
+    }
+    if let Some(dram) = last(|m| m.mem_latency_dram_ns) {
+        bench_items.push(ListItem::new(format!("DRAM:   {:>7.1} ns", dram)));
+    }
     let bench_list = List::new(bench_items).block(
         Block::default()
             .borders(Borders::ALL)
@@ -1084,6 +1797,7 @@ fn draw_details(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rect)
         ListItem::new(format!("Procs:    {:>6}", latest.process_count)),
         ListItem::new(format!("Blocked:  {:>6}", latest.procs_blocked)),
         ListItem::new(format!("FDs:      {:>6}", latest.fd_allocated)),
+        ListItem::new(format_power(latest)),
     ];
     let sys_list = List::new(sys_items).block(
         Block::default()
@@ -1094,6 +1808,26 @@ fn draw_details(f: &mut Frame, metrics_history: &VecDeque<Metrics>, area: Rect)
     f.render_widget(sys_list, cols[3]);
 }
 
+/// Format the power source line for the details panel.
+fn format_power(m: &Metrics) -> String {
+    let profile = m
+        .platform_profile
+        .as_deref()
+        .or(m.cpufreq_epp.as_deref())
+        .or(m.cpufreq_governor.as_deref())
+        .unwrap_or("");
+
+    match (m.ac_online, m.battery_capacity_percent) {
+        (Some(false), Some(pct)) => format!("Power: BAT {:.0}% {}", pct, profile),
+        (_, Some(pct)) => format!("Power: AC {:.0}% {}", pct, profile),
+        (Some(true), None) => format!("Power: AC {}", profile),
+        _ => format!(
+            "Power: {}",
+            if profile.is_empty() { "N/A" } else { profile }
+        ),
+    }
+}
+
 /// Run in headless mode (no TUI, just logging to stdout).
 ///
 /// # Arguments
@@ -1107,19 +1841,38 @@ pub fn run_headless(
     interval: Duration,
 ) -> std::io::Result<()> {
     let csv_file = app.config.csv_file.clone();
-    let history_size = app.config.history_size;
 
-    println!("slow-rs - System Slowness Diagnostic Monitor");
-    println!("=============================================");
-    println!("Logging to: {}", csv_file);
-    println!("Interval: {} seconds", interval.as_secs());
-    println!("Press Ctrl+C to stop.\n");
+    // Keep status output off stdout when a sink writes samples there
+    let mut out: Box<dyn Write> = if app.config.sink_uses_stdout() {
+        Box::new(std::io::stderr())
+    } else {
+        Box::new(std::io::stdout())
+    };
+
+    writeln!(out, "slow-rs - System Slowness Diagnostic Monitor")?;
+    writeln!(out, "=============================================")?;
+    writeln!(out, "Logging to: {}", csv_file)?;
+    writeln!(out, "Interval: {} seconds", interval.as_secs())?;
+    if app.config.record_baseline {
+        writeln!(out, "Recording benchmark baseline")?;
+    } else if let Some(ref baseline) = app.baseline {
+        writeln!(
+            out,
+            "Comparing against baseline from {} ({} samples)",
+            baseline.recorded_at, baseline.samples
+        )?;
+    }
+    writeln!(out, "Press Ctrl+C to stop.\n")?;
 
     while running.load(Ordering::Relaxed) {
         let metrics = app.collect_metrics()?;
+        for error in app.new_output_errors() {
+            eprintln!("Warning: {}", error);
+        }
 
         // Print summary line
-        println!(
+        writeln!(
+            out,
             "[{}] CPU: {:5.1}% | Mem: {:6}/{:6} MB | Load: {:5.2} {:5.2} {:5.2} | Read: {:7.1} MB/s | IOPress: {:5.1}%",
             metrics.datetime,
             metrics.cpu_usage_percent,
@@ -1130,12 +1883,53 @@ pub fn run_headless(
             metrics.load_avg_15,
             metrics.io_read_mb_per_sec.unwrap_or(0.0),
             metrics.io_pressure_some_avg10.unwrap_or(0.0),
-        );
+        )?;
+        if let Some(ref backoff) = metrics.bench_backoff {
+            writeln!(
+                out,
+                "    Benchmarks {} ({}){}",
+                backoff,
+                metrics
+                    .bench_backoff_reason
+                    .as_deref()
+                    .unwrap_or("stressed"),
+                metrics
+                    .benchmarks_skipped
+                    .as_ref()
+                    .map(|s| format!(": {}", s))
+                    .unwrap_or_default()
+            )?;
+        }
+        if let Some(ref regressions) = metrics.baseline_regressions {
+            writeln!(
+                out,
+                "    Baseline regressions (% of baseline): {}",
+                regressions
+            )?;
+        }
 
-        add_metrics(&mut app.metrics_history, metrics, history_size);
+        // Likely causes, judged with the new sample in the history
+        if let Some(latest) = app.metrics_history.back() {
+            for (rank, diagnosis) in diagnose(latest, &app.metrics_history, &app.thresholds)
+                .iter()
+                .enumerate()
+            {
+                writeln!(out, "    Cause #{}: {}", rank + 1, diagnosis.summary())?;
+            }
+            for (event, incident) in app.incidents.changes() {
+                writeln!(
+                    out,
+                    "    Incident {}: {}",
+                    event.name(),
+                    incident.summary(latest.timestamp)
+                )?;
+            }
+        }
         std::thread::sleep(interval);
     }
 
-    println!("\nStopped. Data logged to {}", csv_file);
+    writeln!(out, "\nStopped. Data logged to {}", csv_file)?;
+    save_incidents(&app);
+    write_incident_summary(&mut out, &app)?;
     Ok(())
 }